@@ -26,17 +26,27 @@ pub mod render;
 
 pub mod prelude {
     pub use super::physics::{
-        ColliderBundle, ColliderComponentsSet, ColliderPositionSync, IntoEntity, IntoHandle,
-        JointBuilderComponent, NoUserData, PhysicsHooksWithQuery, PhysicsHooksWithQueryObject,
-        QueryPipelineColliderComponentsQuery, QueryPipelineColliderComponentsSet,
-        RapierConfiguration, RapierPhysicsPlugin, RigidBodyBundle, RigidBodyComponentsSet,
-        RigidBodyPositionSync,
+        apply_explosion, cast_rays_batched, Aerodynamics, CastSensorHit, CharacterAutostep,
+        CharacterCollision, ColliderBundle, ColliderComponentsSet, ColliderPositionSync, Explosion,
+        ExplosionFalloff, ExplosionHit, ExternalForce, ExternalImpulse, FluidVolume, GravityField,
+        GravityFieldKind, GravityScale, IntoEntity, IntoHandle, JointBuilderComponent,
+        JointContactFilter, JointCreationError, JointCreationErrorKind, JointGraph, JointMotor,
+        JointParamsComponent, JointReaction, JointReactions, JointToParent, JointedAssembly,
+        Joints, KinematicCharacterController, KinematicCharacterControllerOutput, MovingPlatform,
+        NoUserData, ParentJointKind, PhysicsHooksWithQuery, PhysicsHooksWithQueryObject,
+        QueryPipelineColliderComponentsQuery, QueryPipelineColliderComponentsSet, Ragdoll,
+        RagdollBody, RagdollBone, RagdollBuilder, RagdollCreationError, RapierConfiguration,
+        RapierPhysicsPlugin, RayCastHit, RayCastQuery, RayCastSensor, RigidBodyBundle,
+        RigidBodyComponentsSet, RigidBodyPositionSync, Rope, RopeAnchor, RopeBuilder,
+        RopeCreationError, RopeEnd, RopeJoint, ShapeCastSensor, Spring, VelocityLimits, WindField,
     };
 
     #[cfg(feature = "dim3")]
     pub use super::physics::{
-        ErrorSum, SharedShapeMesh, VertexFormatError, VertexIndicesMissing,
-        VertexPositionAttributeMissing,
+        AsyncCollider, AsyncColliderError, ConvexDecompositionFailed, ConvexDecompositionParams,
+        ConvexHullFailed, ErrorSum, MeshColliderShape, SceneColliderConvention, SceneColliders,
+        SharedShapeMesh, VehicleWheel, VehicleWheelBundle, VehicleWheelState, VertexFormatError,
+        VertexIndicesMissing, VertexPositionAttributeMissing,
     };
 
     #[cfg(feature = "render")]
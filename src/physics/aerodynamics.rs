@@ -0,0 +1,183 @@
+use crate::physics::{
+    wake_up_dynamic_body, ColliderComponentsSet, IntoHandle, RigidBodyComponentsSet,
+};
+use crate::rapier::data::{ComponentSet, ComponentSetMut, ComponentSetOption, Index};
+use crate::rapier::dynamics::{
+    IslandManager, RigidBodyActivation, RigidBodyColliders, RigidBodyForces, RigidBodyPosition,
+    RigidBodyVelocity,
+};
+use crate::rapier::geometry::{ColliderParent, ColliderShape, AABB};
+use crate::rapier::math::{Isometry, Point, Real, Vector, DIM};
+use crate::rapier::parry::bounding_volume::BoundingVolume;
+use bevy::prelude::*;
+
+pub type AerodynamicsQuery<'a, 'b, 'c> = (
+    Query<'a, (Entity, &'b Aerodynamics)>,
+    Query<'a, (&'c GlobalTransform, &'c WindField)>,
+);
+
+/// A component applying aerodynamic forces to the dynamic rigid-body of the same entity.
+///
+/// The forces are quadratic with the velocity of the rigid-body relative to the wind given by
+/// the `WindField`s. They are computed from the bounding box of the rigid-body's colliders in
+/// its local frame: each face of the box is pushed by the air along its normal, proportionally
+/// to its area. The part of the total force opposed to the relative velocity is the drag, and the
+/// part orthogonal to it is the lift. This makes flat shapes like leaves, parachutes, or wings
+/// behave plausibly, depending on their orientation. The forces are applied at the center of
+/// the bounding box, so a rigid-body with its center of mass away from that center tends to
+/// orient itself with the air flow.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aerodynamics {
+    /// The density of the air around the rigid-body.
+    pub air_density: Real,
+    /// The multiplier of the force opposed to the relative velocity.
+    pub drag_coefficient: Real,
+    /// The multiplier of the force orthogonal to the relative velocity.
+    pub lift_coefficient: Real,
+}
+
+impl Default for Aerodynamics {
+    fn default() -> Self {
+        Self {
+            air_density: 1.2,
+            drag_coefficient: 1.0,
+            lift_coefficient: 1.0,
+        }
+    }
+}
+
+/// A component defining the velocity of the wind inside of its region.
+///
+/// The field is located at the `GlobalTransform` of its entity. The velocities of all the
+/// fields containing the center of the bounding box of a rigid-body with the `Aerodynamics`
+/// component are added together.
+#[derive(Clone)]
+pub struct WindField {
+    /// The velocity of the wind, expressed in the local frame of the entity.
+    pub velocity: Vector<Real>,
+    /// The region affected by this field, in the local frame of the entity. If `None`, the
+    /// field affects all the rigid-bodies.
+    pub region: Option<ColliderShape>,
+}
+
+impl WindField {
+    /// A field with a constant velocity expressed in the local frame of the entity.
+    pub fn new(velocity: Vector<Real>) -> Self {
+        Self {
+            velocity,
+            region: None,
+        }
+    }
+
+    /// Sets the region affected by this field, in the local frame of the entity.
+    pub fn region(mut self, region: ColliderShape) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// The velocity of the wind of this field, located at `field_pos`, at the given world-space point.
+    pub fn velocity_at_point(
+        &self,
+        field_pos: &Isometry<Real>,
+        point: &Point<Real>,
+    ) -> Vector<Real> {
+        match &self.region {
+            Some(region) if !region.contains_point(field_pos, point) => Vector::zeros(),
+            _ => field_pos * self.velocity,
+        }
+    }
+}
+
+/// The bounding box of the colliders of a rigid-body, in its local frame.
+fn local_aabb(
+    rb_colliders: &RigidBodyColliders,
+    colliders: &ColliderComponentsSet,
+) -> Option<AABB> {
+    rb_colliders
+        .0
+        .iter()
+        .filter_map(|co_handle| {
+            let co_parent: Option<&ColliderParent> = colliders.get(co_handle.0);
+            let co_shape: Option<&ColliderShape> = colliders.get(co_handle.0);
+            Some(co_shape?.compute_aabb(&co_parent?.pos_wrt_parent))
+        })
+        .fold(None, |aabb: Option<AABB>, co_aabb| {
+            Some(aabb.map_or(co_aabb, |aabb| aabb.merged(&co_aabb)))
+        })
+}
+
+/// Adds the forces applied by the air to the dynamic rigid-bodies with the `Aerodynamics` component.
+pub(crate) fn apply_aerodynamics(
+    dt: Real,
+    islands: &mut IslandManager,
+    bodies: &mut RigidBodyComponentsSet,
+    colliders: &ColliderComponentsSet,
+    aerodynamics: &Query<(Entity, &Aerodynamics)>,
+    wind_fields: &[(Isometry<Real>, &WindField)],
+) {
+    if dt <= 0.0 {
+        return;
+    }
+
+    for (entity, aero) in aerodynamics.iter() {
+        let handle: Index = entity.handle();
+        let rb_colliders: Option<&RigidBodyColliders> = bodies.get(handle);
+        let aabb = match rb_colliders.and_then(|rb_colliders| local_aabb(rb_colliders, colliders)) {
+            Some(aabb) => aabb,
+            None => continue,
+        };
+
+        let (rb_pos, rb_activation): (&RigidBodyPosition, &RigidBodyActivation) =
+            (bodies.index(handle), bodies.index(handle));
+        let position = rb_pos.position;
+        let center = position * aabb.center();
+        let wind: Vector<Real> = wind_fields
+            .iter()
+            .map(|(field_pos, field)| field.velocity_at_point(field_pos, &center))
+            .sum();
+
+        // Only wake up sleeping bodies if the wind is blowing.
+        if rb_activation.sleeping && wind == Vector::zeros() {
+            continue;
+        }
+
+        let rb_mprops = match wake_up_dynamic_body(islands, bodies, handle) {
+            Some(rb_mprops) => rb_mprops,
+            None => continue,
+        };
+
+        let rb_vels: &RigidBodyVelocity = bodies.index(handle);
+        let relative_velocity = rb_vels.velocity_at_point(&center, &rb_mprops.world_com) - wind;
+        let flow_dir = match relative_velocity.try_normalize(1.0e-6) {
+            Some(dir) => dir,
+            None => continue,
+        };
+
+        // The air pushing each face of the bounding box along its normal.
+        let extents = aabb.extents();
+        let mut force = Vector::zeros();
+
+        for i in 0..DIM {
+            let area: Real = (0..DIM).filter(|j| *j != i).map(|j| extents[j]).product();
+            let normal = position * Vector::ith(i, 1.0);
+            let normal_speed = relative_velocity.dot(&normal);
+            force -= normal * (0.5 * aero.air_density * area * normal_speed * normal_speed.abs());
+        }
+
+        let drag = flow_dir * force.dot(&flow_dir);
+        let lift = force - drag;
+        let mut force = drag * aero.drag_coefficient + lift * aero.lift_coefficient;
+
+        // Light rigid-bodies with a large area would oscillate if the force was larger than
+        // the force needed to cancel their relative velocity during this step.
+        let max_force = rb_mprops.mass() * relative_velocity.norm() / dt;
+        let force_norm = force.norm();
+        if force_norm > max_force {
+            force *= max_force / force_norm;
+        }
+
+        bodies.map_mut_internal(handle, |rb_forces: &mut RigidBodyForces| {
+            rb_forces.apply_force_at_point(&rb_mprops, force, center)
+        });
+    }
+}
@@ -0,0 +1,110 @@
+use crate::rapier::data::ComponentSet;
+use crate::rapier::geometry::{
+    ColliderFlags, ColliderHandle, ColliderPosition, ColliderShape, InteractionGroups, Ray,
+    RayIntersection,
+};
+use crate::rapier::math::Real;
+use crate::rapier::pipeline::QueryPipeline;
+use bevy::tasks::TaskPool;
+
+/// The result of a single ray-cast performed by `cast_rays_batched`.
+pub type RayCastHit = (ColliderHandle, RayIntersection);
+
+/// A single ray-cast to be performed by `cast_rays_batched`.
+#[derive(Copy, Clone, Debug)]
+pub struct RayCastQuery {
+    /// The ray to cast.
+    pub ray: Ray,
+    /// The maximum time-of-impact that can be reported by this cast.
+    ///
+    /// This limits the length of the ray to `ray.dir.norm() * max_toi`.
+    pub max_toi: Real,
+    /// If `true`, an impact at time 0.0 is reported if the ray starts inside of a shape.
+    pub solid: bool,
+    /// The interaction groups tested against the collider's `collision_groups` to determine
+    /// if it should be taken into account by this cast.
+    pub groups: InteractionGroups,
+}
+
+impl RayCastQuery {
+    /// Initializes a solid ray-cast interacting with all the collision groups.
+    pub fn new(ray: Ray, max_toi: Real) -> Self {
+        Self {
+            ray,
+            max_toi,
+            solid: true,
+            groups: InteractionGroups::all(),
+        }
+    }
+
+    /// Sets whether an impact at time 0.0 is reported when the ray starts inside of a shape.
+    pub fn solid(mut self, solid: bool) -> Self {
+        self.solid = solid;
+        self
+    }
+
+    /// Sets the interaction groups used to filter the colliders hit by this ray.
+    pub fn groups(mut self, groups: InteractionGroups) -> Self {
+        self.groups = groups;
+        self
+    }
+}
+
+/// Casts all the given rays against the colliders of the query pipeline, spreading
+/// the work across the threads of `task_pool`.
+///
+/// After this call, `results` has the same length as `rays` and `results[i]` contains
+/// the closest hit found for `rays[i]`, if any. The `results` vector is reused in order
+/// to avoid allocations when the same number of rays are cast at each frame.
+///
+/// The optional `filter` is applied to every ray on top of its interaction groups. It is called
+/// with the index of the ray being cast and the collider being tested. A collider is taken into
+/// account only if `filter` is `None` or returns `true`.
+///
+/// This is typically called with the `ComputeTaskPool` resource and a
+/// `QueryPipelineColliderComponentsSet`.
+pub fn cast_rays_batched<Colliders>(
+    task_pool: &TaskPool,
+    query_pipeline: &QueryPipeline,
+    colliders: &Colliders,
+    rays: &[RayCastQuery],
+    filter: Option<&(dyn Fn(usize, ColliderHandle) -> bool + Sync)>,
+    results: &mut Vec<Option<RayCastHit>>,
+) where
+    Colliders: ComponentSet<ColliderFlags>
+        + ComponentSet<ColliderPosition>
+        + ComponentSet<ColliderShape>
+        + Sync,
+{
+    results.clear();
+    results.resize(rays.len(), None);
+
+    if rays.is_empty() {
+        return;
+    }
+
+    let chunk_size = rays.len() / task_pool.thread_num().max(1) + 1;
+
+    task_pool.scope(|scope| {
+        let chunks = rays.chunks(chunk_size).zip(results.chunks_mut(chunk_size));
+
+        for (chunk_id, (rays, results)) in chunks.enumerate() {
+            scope.spawn(async move {
+                for (i, (query, result)) in rays.iter().zip(results.iter_mut()).enumerate() {
+                    let ray_id = chunk_id * chunk_size + i;
+                    let ray_filter = filter.map(|f| move |h| f(ray_id, h));
+                    *result = query_pipeline.cast_ray_and_get_normal(
+                        colliders,
+                        &query.ray,
+                        query.max_toi,
+                        query.solid,
+                        query.groups,
+                        ray_filter
+                            .as_ref()
+                            .map(|f| f as &dyn Fn(ColliderHandle) -> bool),
+                    );
+                }
+            })
+        }
+    });
+}
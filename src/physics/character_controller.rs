@@ -0,0 +1,487 @@
+use crate::physics::{
+    IntoEntity, QueryPipelineColliderComponentsQuery, QueryPipelineColliderComponentsSet,
+    RapierConfiguration, TimestepMode,
+};
+use crate::rapier::dynamics::{
+    IntegrationParameters, RigidBodyColliders, RigidBodyMassProps, RigidBodyPosition,
+    RigidBodyType, RigidBodyVelocity,
+};
+use crate::rapier::geometry::{
+    ColliderHandle, ColliderParent, ColliderShape, ColliderType, InteractionGroups, Shape, TOI,
+};
+use crate::rapier::math::{Isometry, Real, Vector};
+use crate::rapier::parry::query::{self, TOIStatus};
+use crate::rapier::pipeline::QueryPipeline;
+use bevy::prelude::*;
+
+/// The maximum number of collide-and-slide iterations performed at each frame.
+const MAX_SLIDE_ITERATIONS: usize = 8;
+/// Translations smaller than this are ignored.
+const EPSILON: Real = 1.0e-5;
+
+/// Configuration of the automatic climbing of small steps by a character controller.
+#[derive(Copy, Clone, Debug)]
+pub struct CharacterAutostep {
+    /// The maximum height of the steps that can be climbed automatically.
+    pub max_height: Real,
+    /// The minimum free width required on top of a step for it to be climbed.
+    pub min_width: Real,
+}
+
+impl Default for CharacterAutostep {
+    fn default() -> Self {
+        Self {
+            max_height: 0.25,
+            min_width: 0.5,
+        }
+    }
+}
+
+/// A component moving a kinematic position-based rigid-body with collide-and-slide.
+///
+/// The entity must also contain a `RigidBodyBundle` with a `RigidBodyType::KinematicPositionBased`
+/// body type, and at least one collider attached to it. The shape of the first collider
+/// attached to the rigid-body is the one cast for detecting obstacles.
+///
+/// At each frame, the controller attempts to move the character by `translation`, then
+/// resets it to `None`. The result of the move is written into the
+/// `KinematicCharacterControllerOutput` component of the same entity (automatically
+/// added if missing). Gravity is not applied automatically: it must be part of the
+/// `translation` set by the user.
+#[derive(Clone, Debug)]
+pub struct KinematicCharacterController {
+    /// The translation (in physics units) the character should attempt to perform during
+    /// the next physics step.
+    pub translation: Option<Vector<Real>>,
+    /// The direction pointing upward for this character.
+    pub up: Vector<Real>,
+    /// A small gap kept between the character's shape and the obstacles.
+    pub offset: Real,
+    /// Should the character slide along the obstacles it hits?
+    pub slide: bool,
+    /// The maximum angle (radians) between the floor normal and `up` the character can climb.
+    pub max_slope_climb_angle: Real,
+    /// The minimum angle (radians) between the floor normal and `up` for the character to
+    /// automatically slide down the floor under the effect of the downward part of its
+    /// `translation`.
+    pub min_slope_slide_angle: Real,
+    /// Automatic climbing of small steps, if any.
+    pub autostep: Option<CharacterAutostep>,
+    /// If set, the character is kept on the ground when it is at a distance smaller than this
+    /// value from the floor, unless it is moving upward.
+    pub snap_to_ground: Option<Real>,
+    /// Should the character push the dynamic rigid-bodies it hits?
+    pub push_dynamic_bodies: bool,
+    /// The interaction groups used to filter the obstacles considered by the character.
+    pub filter_groups: InteractionGroups,
+}
+
+impl Default for KinematicCharacterController {
+    fn default() -> Self {
+        Self {
+            translation: None,
+            up: Vector::y(),
+            offset: 0.01,
+            slide: true,
+            max_slope_climb_angle: 45.0_f32.to_radians(),
+            min_slope_slide_angle: 30.0_f32.to_radians(),
+            autostep: None,
+            snap_to_ground: Some(0.2),
+            push_dynamic_bodies: true,
+            filter_groups: InteractionGroups::all(),
+        }
+    }
+}
+
+/// A collision between a character and an obstacle detected during its last move.
+#[derive(Copy, Clone, Debug)]
+pub struct CharacterCollision {
+    /// The entity of the collider hit by the character.
+    pub entity: Entity,
+    /// The translation the character had already performed when the collision happened.
+    pub translation_applied: Vector<Real>,
+    /// The translation the character still wanted to perform when the collision happened.
+    pub translation_remaining: Vector<Real>,
+    /// Geometric information about the impact, in world-space.
+    pub toi: TOI,
+}
+
+/// The result of the last move of a `KinematicCharacterController`.
+#[derive(Clone, Debug, Default)]
+pub struct KinematicCharacterControllerOutput {
+    /// Is the character standing on the ground after its last move?
+    pub grounded: bool,
+    /// The entity of the collider the character is standing on, if any.
+    pub ground: Option<Entity>,
+    /// The translation the character attempted to perform.
+    pub desired_translation: Vector<Real>,
+    /// The translation the character actually performed.
+    pub effective_translation: Vector<Real>,
+    /// All the collisions detected during the last move.
+    pub collisions: Vec<CharacterCollision>,
+}
+
+struct CharacterShapeCaster<'a, 'b, 'c> {
+    query_pipeline: &'a QueryPipeline,
+    colliders: &'a QueryPipelineColliderComponentsSet<'b, 'c, 'a>,
+    filter: &'a dyn Fn(ColliderHandle) -> bool,
+    groups: InteractionGroups,
+    shape: &'a dyn Shape,
+    shape_pos_wrt_body: Isometry<Real>,
+    offset: Real,
+}
+
+impl<'a, 'b, 'c> CharacterShapeCaster<'a, 'b, 'c> {
+    /// Casts the character's shape along `translation`, keeping the `offset` gap.
+    ///
+    /// Returns the collider hit, the distance the character can travel before the hit,
+    /// and the impact.
+    fn cast(
+        &self,
+        body_pos: &Isometry<Real>,
+        translation: &Vector<Real>,
+    ) -> Option<(ColliderHandle, Real, TOI)> {
+        let length = translation.norm();
+        if length <= EPSILON {
+            return None;
+        }
+
+        // Cast a bit further than necessary so we keep the offset gap with obstacles
+        // located right after the end of the translation.
+        let cast_length = length + self.offset;
+        let dir = translation / length;
+        let shape_pos = body_pos * self.shape_pos_wrt_body;
+        let mut ignored = vec![];
+
+        let (handle, toi) = loop {
+            // Ignore the obstacles we are already penetrating but moving away from, so the
+            // character doesn't get stuck.
+            let filter =
+                |handle: ColliderHandle| (self.filter)(handle) && !ignored.contains(&handle);
+            let (handle, toi) = self.query_pipeline.cast_shape(
+                self.colliders,
+                &shape_pos,
+                &(dir * cast_length),
+                self.shape,
+                1.0,
+                self.groups,
+                Some(&filter),
+            )?;
+
+            if toi.status != TOIStatus::Penetrating {
+                break (handle, toi);
+            }
+
+            // The normal reported for penetrations is meaningless so we compute it from
+            // the contact between the character and the obstacle.
+            let (_, co_pos, co_shape, _) = self.colliders.0.get(handle.entity()).ok()?;
+            let contact =
+                query::contact(co_pos, &**co_shape, &shape_pos, self.shape, 0.0).ok()??;
+
+            if dir.dot(&contact.normal1) >= 0.0 {
+                ignored.push(handle);
+            } else {
+                break (
+                    handle,
+                    TOI {
+                        witness1: contact.point1,
+                        witness2: shape_pos.inverse_transform_point(&contact.point2),
+                        normal1: contact.normal1,
+                        normal2: shape_pos.inverse_transform_unit_vector(&contact.normal2),
+                        ..toi
+                    },
+                );
+            }
+        };
+
+        let allowed = (toi.toi * cast_length - self.offset).max(0.0).min(length);
+        Some((handle, allowed, toi))
+    }
+}
+
+fn translated(pos: &Isometry<Real>, translation: &Vector<Real>) -> Isometry<Real> {
+    let mut result = *pos;
+    result.translation.vector += translation;
+    result
+}
+
+impl KinematicCharacterController {
+    fn is_climbable(&self, normal: &Vector<Real>) -> bool {
+        normal.dot(&self.up) > 0.0 && normal.angle(&self.up) <= self.max_slope_climb_angle
+    }
+
+    /// Detects the ground under the character placed at `pos`.
+    fn find_ground(
+        &self,
+        caster: &CharacterShapeCaster,
+        pos: &Isometry<Real>,
+        max_dist: Real,
+    ) -> Option<(ColliderHandle, Real)> {
+        let (handle, dist, toi) = caster.cast(pos, &(-self.up * max_dist))?;
+        if self.is_climbable(&toi.normal1) {
+            Some((handle, dist))
+        } else {
+            None
+        }
+    }
+
+    /// Attempts to climb a step blocking the horizontal part of `translation`.
+    ///
+    /// Returns the translation that brings the character on top of the step, and the
+    /// horizontal translation it performed.
+    fn try_autostep(
+        &self,
+        autostep: &CharacterAutostep,
+        caster: &CharacterShapeCaster,
+        pos: &Isometry<Real>,
+        translation: &Vector<Real>,
+    ) -> Option<(Vector<Real>, Vector<Real>)> {
+        let horizontal = translation - self.up * translation.dot(&self.up);
+        let horizontal_len = horizontal.norm();
+        if horizontal_len <= EPSILON {
+            return None;
+        }
+
+        // Move up.
+        let up_dist = caster
+            .cast(pos, &(self.up * autostep.max_height))
+            .map(|hit| hit.1)
+            .unwrap_or(autostep.max_height);
+        let pos_up = translated(pos, &(self.up * up_dist));
+
+        // Move forward, ensuring there is enough room on top of the step.
+        let dir = horizontal / horizontal_len;
+        let check_len = horizontal_len.max(autostep.min_width);
+        let forward_len = match caster.cast(&pos_up, &(dir * check_len)) {
+            Some((_, dist, _)) if dist < autostep.min_width => return None,
+            Some((_, dist, _)) => dist.min(horizontal_len),
+            None => horizontal_len,
+        };
+        let forward = dir * forward_len;
+        let pos_forward = translated(&pos_up, &forward);
+
+        // Move down, landing on top of the step.
+        let (_, down_dist) = self.find_ground(caster, &pos_forward, up_dist)?;
+
+        if down_dist >= up_dist - EPSILON {
+            // We didn't climb anything.
+            return None;
+        }
+
+        Some((self.up * (up_dist - down_dist) + forward, forward))
+    }
+
+    /// Computes the collide-and-slide movement of a character located at `pos`.
+    fn move_shape(
+        &self,
+        caster: &CharacterShapeCaster,
+        pos: &Isometry<Real>,
+        desired_translation: Vector<Real>,
+    ) -> KinematicCharacterControllerOutput {
+        let mut output = KinematicCharacterControllerOutput {
+            desired_translation,
+            ..Default::default()
+        };
+        let mut translation_remaining = desired_translation;
+        let was_grounded = self
+            .find_ground(caster, pos, self.offset * 2.0 + EPSILON)
+            .is_some();
+
+        for _ in 0..MAX_SLIDE_ITERATIONS {
+            if translation_remaining.norm() <= EPSILON {
+                break;
+            }
+
+            let curr_pos = translated(pos, &output.effective_translation);
+            let (handle, dist, toi) = match caster.cast(&curr_pos, &translation_remaining) {
+                Some(hit) => hit,
+                None => {
+                    output.effective_translation += translation_remaining;
+                    break;
+                }
+            };
+
+            let allowed = translation_remaining.normalize() * dist;
+            output.effective_translation += allowed;
+            translation_remaining -= allowed;
+            output.collisions.push(CharacterCollision {
+                entity: handle.entity(),
+                translation_applied: output.effective_translation,
+                translation_remaining,
+                toi,
+            });
+
+            let normal = *toi.normal1;
+            let climbable = self.is_climbable(&normal);
+
+            if climbable {
+                output.grounded = true;
+                output.ground = Some(handle.entity());
+            } else if let Some(autostep) = &self.autostep {
+                if was_grounded || output.grounded {
+                    let curr_pos = translated(pos, &output.effective_translation);
+                    if let Some((step, forward)) =
+                        self.try_autostep(autostep, caster, &curr_pos, &translation_remaining)
+                    {
+                        output.effective_translation += step;
+                        translation_remaining -= forward;
+                        // Don't keep moving toward the step after climbing it.
+                        translation_remaining -= self.up * translation_remaining.dot(&self.up);
+                        continue;
+                    }
+                }
+            }
+
+            if !self.slide {
+                break;
+            }
+
+            if climbable {
+                // The ground supports the character when it is climbing the slope, or when
+                // the slope is too gentle to slide down.
+                let vertical = translation_remaining.dot(&self.up);
+                let horizontal = translation_remaining - self.up * vertical;
+                let climbing = horizontal.dot(&normal) < 0.0;
+
+                if vertical < 0.0
+                    && (climbing || normal.angle(&self.up) <= self.min_slope_slide_angle)
+                {
+                    translation_remaining = horizontal;
+                }
+            }
+
+            translation_remaining -= normal * translation_remaining.dot(&normal);
+
+            if !climbable {
+                // Don't climb steep slopes.
+                let vertical = translation_remaining.dot(&self.up);
+                if vertical > 0.0 {
+                    translation_remaining -= self.up * vertical;
+                }
+            }
+        }
+
+        let final_pos = translated(pos, &output.effective_translation);
+
+        if let Some(snap_dist) = self.snap_to_ground {
+            if was_grounded && !output.grounded && desired_translation.dot(&self.up) <= 0.0 {
+                if let Some((handle, dist)) = self.find_ground(caster, &final_pos, snap_dist) {
+                    output.effective_translation -= self.up * dist;
+                    output.grounded = true;
+                    output.ground = Some(handle.entity());
+                }
+            }
+        }
+
+        if !output.grounded {
+            let final_pos = translated(pos, &output.effective_translation);
+            if let Some((handle, _)) =
+                self.find_ground(caster, &final_pos, self.offset * 2.0 + EPSILON)
+            {
+                output.grounded = true;
+                output.ground = Some(handle.entity());
+            }
+        }
+
+        output
+    }
+}
+
+/// System responsible for moving the rigid-bodies controlled by a
+/// `KinematicCharacterController`.
+pub fn update_character_controllers_system(
+    mut commands: Commands,
+    (time, configuration, integration_parameters, query_pipeline): (
+        Res<Time>,
+        Res<RapierConfiguration>,
+        Res<IntegrationParameters>,
+        Res<QueryPipeline>,
+    ),
+    colliders: QueryPipelineColliderComponentsQuery,
+    (collider_parents, collider_types): (Query<&ColliderParent>, Query<&ColliderType>),
+    mut controllers: Query<(
+        Entity,
+        &mut KinematicCharacterController,
+        Option<&mut KinematicCharacterControllerOutput>,
+        &mut RigidBodyPosition,
+        &RigidBodyColliders,
+    )>,
+    mut dynamic_bodies: Query<(&mut RigidBodyVelocity, &RigidBodyMassProps, &RigidBodyType)>,
+) {
+    let colliders = QueryPipelineColliderComponentsSet(&colliders);
+    // The duration of the step moving the characters to their new position.
+    let dt = match configuration.timestep_mode {
+        TimestepMode::VariableTimestep => time.delta_seconds().min(integration_parameters.dt),
+        _ => integration_parameters.dt,
+    };
+    let collider_body = |collider: Entity| {
+        collider_parents
+            .get(collider)
+            .map(|co_parent| co_parent.handle.entity())
+            .unwrap_or(collider)
+    };
+
+    for (entity, mut controller, output, mut rb_pos, rb_colliders) in controllers.iter_mut() {
+        let desired_translation = controller.translation.take().unwrap_or(Vector::zeros());
+
+        let co_handle = match rb_colliders.0.first() {
+            Some(handle) => *handle,
+            None => continue,
+        };
+        let shape: &ColliderShape = match colliders.0.get_component(co_handle.entity()) {
+            Ok(shape) => shape,
+            Err(_) => continue,
+        };
+        let shape_pos_wrt_body = collider_parents
+            .get(co_handle.entity())
+            .map(|co_parent| co_parent.pos_wrt_parent)
+            .unwrap_or(Isometry::identity());
+
+        let filter = |handle: ColliderHandle| {
+            collider_body(handle.entity()) != entity
+                && collider_types.get(handle.entity()).ok() != Some(&ColliderType::Sensor)
+        };
+        let caster = CharacterShapeCaster {
+            query_pipeline: &query_pipeline,
+            colliders: &colliders,
+            filter: &filter,
+            groups: controller.filter_groups,
+            shape: &**shape,
+            shape_pos_wrt_body,
+            offset: controller.offset,
+        };
+
+        let new_output = controller.move_shape(&caster, &rb_pos.position, desired_translation);
+
+        if controller.push_dynamic_bodies && dt > 0.0 {
+            for collision in &new_output.collisions {
+                let body = collider_body(collision.entity);
+                if let Ok((mut rb_vels, rb_mprops, rb_type)) = dynamic_bodies.get_mut(body) {
+                    if !rb_type.is_dynamic() {
+                        continue;
+                    }
+
+                    let dir = -*collision.toi.normal1;
+                    let push_vel = collision.translation_remaining.dot(&dir) / dt;
+                    let point = collision.toi.witness1;
+                    let body_vel = rb_vels
+                        .velocity_at_point(&point, &rb_mprops.world_com)
+                        .dot(&dir);
+
+                    if push_vel > body_vel {
+                        let impulse = dir * (push_vel - body_vel) * rb_mprops.mass();
+                        rb_vels.apply_impulse_at_point(rb_mprops, impulse, point);
+                    }
+                }
+            }
+        }
+
+        rb_pos.next_position = translated(&rb_pos.position, &new_output.effective_translation);
+
+        if let Some(mut output) = output {
+            *output = new_output;
+        } else {
+            commands.entity(entity).insert(new_output);
+        }
+    }
+}
@@ -45,6 +45,7 @@ impl ColliderHandleComponent {
 ///
 /// This component should not be created manually. It is automatically created and
 /// added to an entity by the `JointBuilderComponent`.
+#[derive(Copy, Clone, Debug)]
 pub struct JointHandleComponent {
     handle: JointHandle,
     entity1: Entity,
@@ -76,14 +77,40 @@ impl JointHandleComponent {
     }
 }
 
+/// A component holding the parameters of a joint added to the JointSet resource.
+///
+/// This component is automatically added next to the `JointHandleComponent`. Any modification
+/// of its parameters (anchors, limits, etc.) is applied to the Rapier joint before the next
+/// timestep, and wakes up the rigid-bodies attached to the joint. The impulses accumulated by
+/// the Rapier joint, and the state of its motor, are kept.
+#[derive(Copy, Clone)]
+pub struct JointParamsComponent(pub JointParams);
+
+impl From<JointParams> for JointParamsComponent {
+    fn from(params: JointParams) -> Self {
+        Self(params)
+    }
+}
+
 /// Component responsible for initializing a Rapier joint.
 ///
 /// This is a transient component that will be automatically replaced by a `JointHandleComponent`
-/// once the Rapier joint it describes has been created and added to the `JointSet` resource.
+/// and a `JointParamsComponent` once the Rapier joint it describes has been created and added
+/// to the `JointSet` resource.
+///
+/// The joint stays pending while one of its entities has no rigid-body yet. It is abandoned,
+/// and a `JointCreationError` event is sent, if one of its entities is despawned, or if it is
+/// still pending after `RapierConfiguration::joint_creation_timeout` frames.
+///
+/// By default, the colliders of the two rigid-bodies attached by the joint can collide with
+/// each other. This can be disabled with `JointBuilderComponent::contacts_enabled`.
+#[derive(Copy, Clone)]
 pub struct JointBuilderComponent {
     pub(crate) params: JointParams,
     pub(crate) entity1: Entity,
     pub(crate) entity2: Entity,
+    pub(crate) pending_frames: u32,
+    pub(crate) contacts_enabled: bool,
 }
 
 impl JointBuilderComponent {
@@ -96,8 +123,111 @@ impl JointBuilderComponent {
             params: joint.into(),
             entity1,
             entity2,
+            pending_frames: 0,
+            contacts_enabled: true,
         }
     }
+
+    /// Sets whether the colliders of the two rigid-bodies attached by this joint can collide
+    /// with each other.
+    ///
+    /// Contacts are disabled by the physics hooks of the plugin, which enable
+    /// `ActiveHooks::FILTER_CONTACT_PAIRS` on the colliders of these rigid-bodies. The custom
+    /// `PhysicsHooksWithQuery` are still only called for the colliders on which this flag was
+    /// enabled by the user.
+    ///
+    /// The plugin can't tell its flag apart from the user's, so the user must not toggle this
+    /// flag on the colliders of these rigid-bodies while the joint exists. If it is enabled
+    /// after the joint is created, it is considered enabled by the plugin: the custom hooks
+    /// aren't called for these colliders, and the flag is disabled once the joint is removed.
+    pub fn contacts_enabled(mut self, enabled: bool) -> Self {
+        self.contacts_enabled = enabled;
+        self
+    }
+
+    /// The number of frames this joint has been waiting for the rigid-bodies of its entities.
+    pub fn pending_frames(&self) -> u32 {
+        self.pending_frames
+    }
+}
+
+/// The reason why a joint described by a `JointBuilderComponent` could not be created.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum JointCreationErrorKind {
+    /// The entity doesn't exist, e.g., because it has been despawned.
+    EntityNotFound,
+    /// The entity still has no rigid-body after `RapierConfiguration::joint_creation_timeout`
+    /// frames.
+    RigidBodyNotFound,
+}
+
+/// An event sent when a joint described by a `JointBuilderComponent` is abandoned.
+///
+/// The `JointBuilderComponent` is removed from its entity, or from its `Joints` component,
+/// when this event is sent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct JointCreationError {
+    /// The entity with the `JointBuilderComponent`, or with the `Joints` component.
+    pub joint: Entity,
+    /// The entity attached to the joint that caused the error.
+    pub entity: Entity,
+    /// The reason of the error.
+    pub kind: JointCreationErrorKind,
+}
+
+/// A component describing several joints, typically attached to the rigid-body of its entity.
+///
+/// This avoids spawning a dedicated entity with a `JointBuilderComponent` for each joint, e.g.,
+/// for chains of rigid-bodies. The joints are created like the ones described by
+/// `JointBuilderComponent`s, and their handles are then listed by this component. They are
+/// removed from the `JointSet` resource when this component is removed, or its entity
+/// despawned. The joints attached to a despawned rigid-body are removed from the `JointSet`
+/// resource and from this component.
+#[derive(Clone, Default)]
+pub struct Joints {
+    pub(crate) builders: Vec<JointBuilderComponent>,
+    pub(crate) handles: Vec<JointHandleComponent>,
+}
+
+impl Joints {
+    /// A component without any joint.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a joint between the given entities.
+    pub fn joint<J>(mut self, joint: J, entity1: Entity, entity2: Entity) -> Self
+    where
+        J: Into<JointParams>,
+    {
+        self.add(joint, entity1, entity2);
+        self
+    }
+
+    /// Adds a joint between the given entities, created at the next frame.
+    pub fn add<J>(&mut self, joint: J, entity1: Entity, entity2: Entity)
+    where
+        J: Into<JointParams>,
+    {
+        self.builders
+            .push(JointBuilderComponent::new(joint, entity1, entity2));
+    }
+
+    /// Adds the joint described by the given builder, e.g., to configure its options.
+    pub fn joint_builder(mut self, builder: JointBuilderComponent) -> Self {
+        self.builders.push(builder);
+        self
+    }
+
+    /// The joints that have been created and added to the `JointSet` resource.
+    pub fn handles(&self) -> &[JointHandleComponent] {
+        &self.handles
+    }
+
+    /// The joints waiting for the rigid-bodies of their entities.
+    pub fn pending(&self) -> &[JointBuilderComponent] {
+        &self.builders
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -0,0 +1,271 @@
+use crate::physics::IntoEntity;
+use crate::rapier::data::ComponentSet;
+use crate::rapier::dynamics::{
+    RigidBodyActivation, RigidBodyMassProps, RigidBodyType, RigidBodyVelocity,
+};
+use crate::rapier::geometry::{
+    Ball, ColliderFlags, ColliderHandle, ColliderParent, ColliderPosition, ColliderShape,
+    InteractionGroups, Ray,
+};
+use crate::rapier::math::{Isometry, Point, Real, Vector};
+use crate::rapier::pipeline::QueryPipeline;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// The rigid-body components modified by `apply_explosion`.
+pub type ExplosionBodiesQuery<'a, 'b> = Query<
+    'a,
+    (
+        &'b mut RigidBodyVelocity,
+        &'b mut RigidBodyActivation,
+        &'b RigidBodyMassProps,
+        &'b RigidBodyType,
+    ),
+>;
+
+/// How the impulse of an `Explosion` decreases with the distance to its center.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ExplosionFalloff {
+    /// The impulse is the same everywhere inside of the explosion's radius.
+    Constant,
+    /// The impulse decreases linearly, down to zero at the explosion's radius.
+    Linear,
+    /// The impulse decreases with the square of the distance, and is cut at the
+    /// explosion's radius. The impulse at a distance of 1 is equal to `Explosion::impulse`.
+    InverseSquare,
+}
+
+/// A radial impulse applied by `apply_explosion`.
+#[derive(Copy, Clone, Debug)]
+pub struct Explosion {
+    /// The center of the explosion.
+    pub center: Point<Real>,
+    /// The maximum distance between the center and a collider for its rigid-body to be affected.
+    pub radius: Real,
+    /// The magnitude of the impulse at the center of the explosion.
+    pub impulse: Real,
+    /// How the impulse decreases with the distance to the center.
+    pub falloff: ExplosionFalloff,
+    /// If `true`, a rigid-body is only affected if a ray cast from the center to its closest
+    /// collider isn't blocked by another collider.
+    pub line_of_sight: bool,
+    /// The interaction groups used to filter the colliders affected by the explosion, and the
+    /// colliders blocking the line-of-sight.
+    pub groups: InteractionGroups,
+}
+
+impl Explosion {
+    /// An explosion with a linear falloff and no line-of-sight check.
+    pub fn new(center: Point<Real>, radius: Real, impulse: Real) -> Self {
+        Self {
+            center,
+            radius,
+            impulse,
+            falloff: ExplosionFalloff::Linear,
+            line_of_sight: false,
+            groups: InteractionGroups::all(),
+        }
+    }
+
+    /// Sets how the impulse decreases with the distance to the center.
+    pub fn falloff(mut self, falloff: ExplosionFalloff) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    /// Sets whether the rigid-bodies hidden behind other colliders are affected.
+    pub fn line_of_sight(mut self, line_of_sight: bool) -> Self {
+        self.line_of_sight = line_of_sight;
+        self
+    }
+
+    /// Sets the interaction groups used to filter the colliders affected by the explosion.
+    pub fn groups(mut self, groups: InteractionGroups) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// The magnitude of the impulse at the given distance from the center.
+    pub fn impulse_at_distance(&self, distance: Real) -> Real {
+        if distance > self.radius {
+            return 0.0;
+        }
+
+        match self.falloff {
+            ExplosionFalloff::Constant => self.impulse,
+            ExplosionFalloff::Linear => {
+                if self.radius > 0.0 {
+                    self.impulse * (1.0 - distance / self.radius)
+                } else {
+                    self.impulse
+                }
+            }
+            ExplosionFalloff::InverseSquare => self.impulse / distance.max(1.0).powi(2),
+        }
+    }
+}
+
+/// A dynamic rigid-body affected by an explosion.
+#[derive(Copy, Clone, Debug)]
+pub struct ExplosionHit {
+    /// The entity of the rigid-body.
+    pub entity: Entity,
+    /// The distance between the center of the explosion and the closest collider of the rigid-body.
+    pub distance: Real,
+    /// The impulse applied to the center of mass of the rigid-body.
+    pub impulse: Vector<Real>,
+}
+
+/// Applies the impulse of an explosion to all the dynamic rigid-bodies with a collider within
+/// its radius, waking them up if they are sleeping.
+///
+/// The impulse is directed from the center of the explosion to the center of mass of each
+/// rigid-body, and its magnitude depends on the distance between the center and the closest
+/// collider of the rigid-body. The affected rigid-bodies are returned, e.g., for applying damage.
+///
+/// This is typically called with a `QueryPipelineColliderComponentsSet`.
+pub fn apply_explosion<Colliders>(
+    query_pipeline: &QueryPipeline,
+    colliders: &Colliders,
+    collider_parents: &Query<&ColliderParent>,
+    bodies: &mut ExplosionBodiesQuery,
+    explosion: &Explosion,
+) -> Vec<ExplosionHit>
+where
+    Colliders:
+        ComponentSet<ColliderFlags> + ComponentSet<ColliderPosition> + ComponentSet<ColliderShape>,
+{
+    let body_of = |collider: ColliderHandle| {
+        let entity = collider.entity();
+        collider_parents
+            .get(entity)
+            .map(|co_parent| co_parent.handle.entity())
+            .unwrap_or(entity)
+    };
+
+    // The closest point of each rigid-body to the center.
+    let mut closest_points: HashMap<Entity, (Real, Point<Real>)> = HashMap::new();
+    let shape_pos = Isometry::from(explosion.center.coords);
+    let shape = Ball::new(explosion.radius);
+
+    query_pipeline.intersections_with_shape(
+        colliders,
+        &shape_pos,
+        &shape,
+        explosion.groups,
+        None,
+        |handle| {
+            let co_shape: &ColliderShape = colliders.index(handle.0);
+            let co_pos: &ColliderPosition = colliders.index(handle.0);
+            let point = co_shape
+                .project_point(co_pos, &explosion.center, true)
+                .point;
+            let distance = na::distance(&point, &explosion.center);
+
+            let closest = closest_points
+                .entry(body_of(handle))
+                .or_insert((Real::MAX, point));
+            if distance < closest.0 {
+                *closest = (distance, point);
+            }
+
+            true
+        },
+    );
+
+    // Don't let the colliders containing the center block the line-of-sight.
+    let not_containing_center = |handle: ColliderHandle| {
+        let co_shape: &ColliderShape = colliders.index(handle.0);
+        let co_pos: &ColliderPosition = colliders.index(handle.0);
+        !co_shape.contains_point(co_pos, &explosion.center)
+    };
+    let mut hits = vec![];
+
+    for (entity, (distance, point)) in closest_points {
+        let (mut rb_vels, mut rb_activation, rb_mprops, rb_type) = match bodies.get_mut(entity) {
+            Ok(body) => body,
+            Err(_) => continue,
+        };
+
+        if !rb_type.is_dynamic() {
+            continue;
+        }
+
+        if explosion.line_of_sight && distance > 0.0 {
+            let ray = Ray::new(explosion.center, point - explosion.center);
+            let hit = query_pipeline.cast_ray(
+                colliders,
+                &ray,
+                1.0,
+                true,
+                explosion.groups,
+                Some(&not_containing_center),
+            );
+
+            if let Some((handle, _)) = hit {
+                if body_of(handle) != entity {
+                    continue;
+                }
+            }
+        }
+
+        let magnitude = explosion.impulse_at_distance(distance);
+        let direction = (rb_mprops.world_com - explosion.center)
+            .try_normalize(1.0e-6)
+            .unwrap_or_else(Vector::y);
+        let impulse = direction * magnitude;
+
+        if magnitude != 0.0 {
+            rb_vels.apply_impulse(rb_mprops, impulse);
+            rb_activation.wake_up(true);
+        }
+
+        hits.push(ExplosionHit {
+            entity,
+            distance,
+            impulse,
+        });
+    }
+
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explosion(falloff: ExplosionFalloff) -> Explosion {
+        Explosion::new(Point::origin(), 4.0, 8.0).falloff(falloff)
+    }
+
+    #[test]
+    fn constant_falloff() {
+        let explosion = explosion(ExplosionFalloff::Constant);
+        assert_eq!(explosion.impulse_at_distance(0.0), 8.0);
+        assert_eq!(explosion.impulse_at_distance(4.0), 8.0);
+        assert_eq!(explosion.impulse_at_distance(4.5), 0.0);
+    }
+
+    #[test]
+    fn linear_falloff() {
+        let explosion = explosion(ExplosionFalloff::Linear);
+        assert_eq!(explosion.impulse_at_distance(0.0), 8.0);
+        assert_eq!(explosion.impulse_at_distance(1.0), 6.0);
+        assert_eq!(explosion.impulse_at_distance(4.0), 0.0);
+        assert_eq!(explosion.impulse_at_distance(4.5), 0.0);
+
+        let point = Explosion::new(Point::origin(), 0.0, 8.0);
+        assert_eq!(point.impulse_at_distance(0.0), 8.0);
+    }
+
+    #[test]
+    fn inverse_square_falloff() {
+        let explosion = explosion(ExplosionFalloff::InverseSquare);
+        // The impulse is capped below a distance of 1.
+        assert_eq!(explosion.impulse_at_distance(0.0), 8.0);
+        assert_eq!(explosion.impulse_at_distance(1.0), 8.0);
+        assert_eq!(explosion.impulse_at_distance(2.0), 2.0);
+        assert_eq!(explosion.impulse_at_distance(4.0), 0.5);
+        assert_eq!(explosion.impulse_at_distance(4.5), 0.0);
+    }
+}
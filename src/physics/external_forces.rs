@@ -0,0 +1,178 @@
+use crate::physics::{IntoHandle, RigidBodyComponentsSet};
+use crate::rapier::data::{ComponentSet, ComponentSetMut, ComponentSetOption, Index};
+use crate::rapier::dynamics::{
+    IslandManager, RigidBodyActivation, RigidBodyForces, RigidBodyHandle, RigidBodyMassProps,
+    RigidBodyPosition, RigidBodyType, RigidBodyVelocity,
+};
+use crate::rapier::math::{AngVector, Point, Vector};
+use bevy::prelude::*;
+
+pub type ExternalForcesQuery<'a, 'b, 'c> = (
+    Query<'a, (Entity, &'b ExternalForce)>,
+    Query<'a, (Entity, &'c mut ExternalImpulse)>,
+);
+
+#[cfg(feature = "dim2")]
+type ExternalVector = Vec2;
+#[cfg(feature = "dim2")]
+type ExternalAngVector = f32;
+#[cfg(feature = "dim3")]
+type ExternalVector = Vec3;
+#[cfg(feature = "dim3")]
+type ExternalAngVector = Vec3;
+
+#[cfg(feature = "dim2")]
+fn ang_vector(v: ExternalAngVector) -> AngVector<f32> {
+    v
+}
+
+#[cfg(feature = "dim3")]
+fn ang_vector(v: ExternalAngVector) -> AngVector<f32> {
+    v.into()
+}
+
+/// A force and torque continuously applied to the dynamic rigid-body of the same entity.
+///
+/// The force is applied at each physics step (including each step performed during a single
+/// frame with the `TimestepMode::InterpolatedTimestep`) until it is set back to zero.
+/// All the quantities are expressed in physics units (i.e. not multiplied by
+/// `RapierConfiguration::scale`). The vectors are `Vec2` in 2D and `Vec3` in 3D, and the
+/// torque is a `f32` in 2D and a `Vec3` in 3D.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ExternalForce {
+    /// The force applied to the rigid-body.
+    pub force: ExternalVector,
+    /// The torque applied to the rigid-body.
+    pub torque: ExternalAngVector,
+    /// The world-space point where `force` is applied. If `None`, the force is applied at the
+    /// center of mass of the rigid-body.
+    pub point: Option<ExternalVector>,
+}
+
+impl ExternalForce {
+    /// A force applied at the given world-space point.
+    pub fn at_point(force: ExternalVector, point: ExternalVector) -> Self {
+        Self {
+            force,
+            point: Some(point),
+            ..Default::default()
+        }
+    }
+}
+
+/// An impulse and torque impulse applied once to the dynamic rigid-body of the same entity.
+///
+/// The impulse is applied right before the next physics step and is then reset to zero. If
+/// no step is performed during a frame (which can happen with the
+/// `TimestepMode::InterpolatedTimestep`), the impulse is kept until the next step.
+/// All the quantities are expressed in physics units (i.e. not multiplied by
+/// `RapierConfiguration::scale`). The vectors are `Vec2` in 2D and `Vec3` in 3D, and the
+/// torque impulse is a `f32` in 2D and a `Vec3` in 3D.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ExternalImpulse {
+    /// The impulse applied to the rigid-body.
+    pub impulse: ExternalVector,
+    /// The torque impulse applied to the rigid-body.
+    pub torque_impulse: ExternalAngVector,
+    /// The world-space point where `impulse` is applied. If `None`, the impulse is applied at
+    /// the center of mass of the rigid-body.
+    pub point: Option<ExternalVector>,
+}
+
+impl ExternalImpulse {
+    /// An impulse applied at the given world-space point.
+    pub fn at_point(impulse: ExternalVector, point: ExternalVector) -> Self {
+        Self {
+            impulse,
+            point: Some(point),
+            ..Default::default()
+        }
+    }
+}
+
+/// Wakes-up the given rigid-body if it is dynamic and returns its mass properties.
+///
+/// The world-space mass properties are updated with the current position of the rigid-body
+/// because Rapier only updates them during the next step. Returns `None` if the rigid-body
+/// doesn't exist or isn't dynamic.
+pub(crate) fn wake_up_dynamic_body(
+    islands: &mut IslandManager,
+    bodies: &mut RigidBodyComponentsSet,
+    handle: Index,
+) -> Option<RigidBodyMassProps> {
+    let rb_type: &RigidBodyType = bodies.get(handle)?;
+    if !rb_type.is_dynamic() {
+        return None;
+    }
+
+    let rb_activation: &RigidBodyActivation = bodies.index(handle);
+    if rb_activation.sleeping {
+        islands.wake_up(bodies, RigidBodyHandle(handle), true);
+    }
+
+    let rb_pos: &RigidBodyPosition = bodies.index(handle);
+    let mut rb_mprops: RigidBodyMassProps = *bodies.index(handle);
+    rb_mprops.update_world_mass_properties(&rb_pos.position);
+    Some(rb_mprops)
+}
+
+/// Adds the `ExternalForce`s to the forces of their rigid-bodies.
+pub(crate) fn apply_external_forces(
+    islands: &mut IslandManager,
+    bodies: &mut RigidBodyComponentsSet,
+    external_forces: &Query<(Entity, &ExternalForce)>,
+) {
+    for (entity, external_force) in external_forces.iter() {
+        if *external_force == ExternalForce::default() {
+            continue;
+        }
+
+        let handle = entity.handle();
+        let rb_mprops = match wake_up_dynamic_body(islands, bodies, handle) {
+            Some(rb_mprops) => rb_mprops,
+            None => continue,
+        };
+
+        let force: Vector<f32> = external_force.force.into();
+        let torque = ang_vector(external_force.torque);
+        let point = external_force
+            .point
+            .map(|point| Point::from(Vector::from(point)))
+            .unwrap_or(rb_mprops.world_com);
+
+        bodies.map_mut_internal(handle, |rb_forces: &mut RigidBodyForces| {
+            rb_forces.apply_force_at_point(&rb_mprops, force, point);
+            rb_forces.torque += torque;
+        });
+    }
+}
+
+/// Applies the `ExternalImpulse`s to the velocities of their rigid-bodies, and resets them.
+pub(crate) fn apply_external_impulses(
+    islands: &mut IslandManager,
+    bodies: &mut RigidBodyComponentsSet,
+    external_impulses: &mut Query<(Entity, &mut ExternalImpulse)>,
+) {
+    for (entity, mut external_impulse) in external_impulses.iter_mut() {
+        if *external_impulse == ExternalImpulse::default() {
+            continue;
+        }
+
+        let handle = entity.handle();
+        if let Some(rb_mprops) = wake_up_dynamic_body(islands, bodies, handle) {
+            let impulse: Vector<f32> = external_impulse.impulse.into();
+            let torque_impulse = ang_vector(external_impulse.torque_impulse);
+            let point = external_impulse
+                .point
+                .map(|point| Point::from(Vector::from(point)))
+                .unwrap_or(rb_mprops.world_com);
+
+            bodies.map_mut_internal(handle, |rb_vels: &mut RigidBodyVelocity| {
+                rb_vels.apply_impulse_at_point(&rb_mprops, impulse, point);
+                rb_vels.apply_torque_impulse(&rb_mprops, torque_impulse);
+            });
+        }
+
+        *external_impulse = ExternalImpulse::default();
+    }
+}
@@ -0,0 +1,237 @@
+use crate::physics::{
+    wake_up_dynamic_body, ColliderComponentsSet, IntoHandle, RigidBodyComponentsSet,
+};
+use crate::rapier::data::{ComponentSetMut, ComponentSetOption};
+use crate::rapier::dynamics::{
+    IslandManager, RigidBodyActivation, RigidBodyColliders, RigidBodyForces, RigidBodyHandle,
+    RigidBodyVelocity,
+};
+use crate::rapier::geometry::{
+    ColliderHandle, ColliderParent, ColliderPosition, ColliderShape, ColliderType, NarrowPhase,
+    AABB,
+};
+use crate::rapier::math::{Point, Real, Vector};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// A component turning a sensor collider into a volume of fluid.
+///
+/// The dynamic rigid-bodies intersecting the fluid are subject to buoyancy, and to linear
+/// and angular drag. The submerged part of each collider is approximated from the
+/// intersection of its AABB with the AABB of the fluid, so this works best with fluid volumes
+/// shaped as cuboids aligned with the world axes.
+#[derive(Copy, Clone, Debug)]
+pub struct FluidVolume {
+    /// The density of the fluid. A rigid-body floats if its density is smaller than this.
+    pub density: Real,
+    /// The linear damping applied to the rigid-bodies fully submerged in the fluid.
+    pub linear_drag: Real,
+    /// The angular damping applied to the rigid-bodies fully submerged in the fluid.
+    pub angular_drag: Real,
+}
+
+impl Default for FluidVolume {
+    fn default() -> Self {
+        Self {
+            density: 1.0,
+            linear_drag: 1.0,
+            angular_drag: 1.0,
+        }
+    }
+}
+
+/// The part of a rigid-body submerged in a fluid volume.
+#[derive(Default)]
+struct Submersion {
+    /// The submerged volume of the rigid-body.
+    volume: Real,
+    /// The sum of the centers of the submerged parts, weighted by their volume.
+    weighted_center: Vector<Real>,
+}
+
+/// The volume of the given shape, or `None` if it has no volume.
+fn shape_volume(shape: &ColliderShape) -> Option<Real> {
+    let inv_volume = shape.mass_properties(1.0).inv_mass;
+
+    if inv_volume > 0.0 {
+        Some(1.0 / inv_volume)
+    } else {
+        None
+    }
+}
+
+/// The total volume of the solid colliders attached to the given rigid-body.
+fn body_volume(
+    rb_handle: RigidBodyHandle,
+    bodies: &RigidBodyComponentsSet,
+    colliders: &ColliderComponentsSet,
+) -> Real {
+    let rb_colliders: Option<&RigidBodyColliders> = bodies.get(rb_handle.0);
+
+    rb_colliders
+        .into_iter()
+        .flat_map(|rb_colliders| rb_colliders.0.iter())
+        .filter_map(|co_handle| {
+            let (co_type, co_shape): (Option<&ColliderType>, Option<&ColliderShape>) =
+                (colliders.get(co_handle.0), colliders.get(co_handle.0));
+
+            match (co_type, co_shape) {
+                (Some(co_type), Some(co_shape)) if !co_type.is_sensor() => shape_volume(co_shape),
+                _ => None,
+            }
+        })
+        .sum()
+}
+
+fn aabb_volume(aabb: &AABB) -> Real {
+    aabb.extents().iter().product()
+}
+
+fn aabb_intersection(aabb1: &AABB, aabb2: &AABB) -> Option<AABB> {
+    let mins = aabb1.mins.sup(&aabb2.mins);
+    let maxs = aabb1.maxs.inf(&aabb2.maxs);
+
+    if mins.iter().zip(maxs.iter()).all(|(min, max)| min < max) {
+        Some(AABB::new(mins, maxs))
+    } else {
+        None
+    }
+}
+
+/// Applies the buoyancy and drag of the `FluidVolume`s to the dynamic rigid-bodies intersecting them.
+pub(crate) fn apply_fluid_volumes(
+    gravity: &Vector<Real>,
+    dt: Real,
+    (islands, narrow_phase): (&mut IslandManager, &NarrowPhase),
+    bodies: &mut RigidBodyComponentsSet,
+    colliders: &ColliderComponentsSet,
+    fluid_volumes: &Query<(Entity, &FluidVolume)>,
+) {
+    for (entity, fluid) in fluid_volumes.iter() {
+        let fluid_handle: ColliderHandle = entity.handle();
+        let (fluid_shape, fluid_pos): (Option<&ColliderShape>, Option<&ColliderPosition>) =
+            (colliders.get(fluid_handle.0), colliders.get(fluid_handle.0));
+        let fluid_aabb = match (fluid_shape, fluid_pos) {
+            (Some(shape), Some(pos)) => shape.compute_aabb(pos),
+            _ => continue,
+        };
+
+        let mut submersions: HashMap<RigidBodyHandle, Submersion> = HashMap::new();
+
+        // NOTE: the intersection flag is only updated by Rapier if the sensor has intersection
+        //       events enabled. So we just rely on the broad-phase pairs, and on the AABB
+        //       intersections computed below.
+        for (co1, co2, _) in narrow_phase.intersections_with(fluid_handle) {
+            let other = if co1 == fluid_handle { co2 } else { co1 };
+            // The colliders despawned during this frame are still in the narrow-phase.
+            let (co_parent, co_type, co_shape, co_pos): (
+                Option<&ColliderParent>,
+                Option<&ColliderType>,
+                Option<&ColliderShape>,
+                Option<&ColliderPosition>,
+            ) = (
+                colliders.get(other.0),
+                colliders.get(other.0),
+                colliders.get(other.0),
+                colliders.get(other.0),
+            );
+
+            let (rb_handle, co_shape, co_pos) = match (co_parent, co_type, co_shape, co_pos) {
+                (Some(co_parent), Some(co_type), Some(co_shape), Some(co_pos))
+                    if !co_type.is_sensor() =>
+                {
+                    (co_parent.handle, co_shape, co_pos)
+                }
+                _ => continue,
+            };
+
+            let co_volume = match shape_volume(co_shape) {
+                Some(co_volume) => co_volume,
+                None => continue,
+            };
+            let co_aabb = co_shape.compute_aabb(co_pos);
+            let submersion = submersions.entry(rb_handle).or_default();
+
+            if let Some(submerged_aabb) = aabb_intersection(&co_aabb, &fluid_aabb) {
+                let fraction = aabb_volume(&submerged_aabb) / aabb_volume(&co_aabb);
+                let volume = co_volume * fraction;
+                submersion.volume += volume;
+                submersion.weighted_center += submerged_aabb.center().coords * volume;
+            }
+        }
+
+        for (rb_handle, submersion) in submersions {
+            if submersion.volume <= 0.0 {
+                continue;
+            }
+
+            // Bodies resting in the fluid are left asleep.
+            let rb_activation: Option<&RigidBodyActivation> = bodies.get(rb_handle.0);
+            if rb_activation.map(|rb_activation| rb_activation.sleeping) != Some(false) {
+                continue;
+            }
+
+            let rb_mprops = match wake_up_dynamic_body(islands, bodies, rb_handle.0) {
+                Some(rb_mprops) => rb_mprops,
+                None => continue,
+            };
+            let center_of_buoyancy = Point::from(submersion.weighted_center / submersion.volume);
+            let total_volume = body_volume(rb_handle, bodies, colliders);
+            let fraction = (submersion.volume / total_volume.max(Real::EPSILON)).min(1.0);
+
+            bodies.map_mut_internal(rb_handle.0, |rb_forces: &mut RigidBodyForces| {
+                let buoyancy =
+                    -gravity * (fluid.density * submersion.volume * rb_forces.gravity_scale);
+                rb_forces.apply_force_at_point(&rb_mprops, buoyancy, center_of_buoyancy);
+            });
+
+            // Apply the drag the same way Rapier applies damping.
+            bodies.map_mut_internal(rb_handle.0, |rb_vels: &mut RigidBodyVelocity| {
+                rb_vels.linvel *= 1.0 / (1.0 + dt * fluid.linear_drag * fraction);
+                rb_vels.angvel *= 1.0 / (1.0 + dt * fluid.angular_drag * fraction);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rapier::math::DIM;
+
+    fn aabb(mins: Real, maxs: Real) -> AABB {
+        AABB::new(
+            Point::from(Vector::repeat(mins)),
+            Point::from(Vector::repeat(maxs)),
+        )
+    }
+
+    #[test]
+    fn shape_volumes() {
+        let ball_volume = shape_volume(&ColliderShape::ball(1.0)).unwrap();
+        #[cfg(feature = "dim2")]
+        let expected = std::f32::consts::PI;
+        #[cfg(feature = "dim3")]
+        let expected = 4.0 / 3.0 * std::f32::consts::PI;
+        assert!((ball_volume - expected).abs() < 1.0e-4);
+
+        let segment = ColliderShape::segment(Point::origin(), Point::from(Vector::x()));
+        assert_eq!(shape_volume(&segment), None);
+    }
+
+    #[test]
+    fn aabb_volumes_and_intersections() {
+        assert_eq!(
+            aabb_volume(&aabb(-1.0, 1.0)),
+            (2.0 as Real).powi(DIM as i32)
+        );
+
+        let intersection = aabb_intersection(&aabb(-1.0, 1.0), &aabb(0.5, 3.0)).unwrap();
+        assert_eq!(intersection, aabb(0.5, 1.0));
+        assert_eq!(aabb_volume(&intersection), (0.5 as Real).powi(DIM as i32));
+
+        // Touching or disjoint boxes have no submerged part.
+        assert_eq!(aabb_intersection(&aabb(-1.0, 1.0), &aabb(1.0, 3.0)), None);
+        assert_eq!(aabb_intersection(&aabb(-1.0, 1.0), &aabb(2.0, 3.0)), None);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::physics::{wake_up_dynamic_body, IntoHandle, RigidBodyComponentsSet};
+use crate::rapier::data::{ComponentSet, ComponentSetMut, ComponentSetOption, Index};
+use crate::rapier::dynamics::{IslandManager, RigidBodyForces, RigidBodyMassProps};
+use crate::rapier::geometry::ColliderShape;
+use crate::rapier::math::{Isometry, Point, Real, Vector};
+use bevy::prelude::*;
+
+pub type GravityQuery<'a, 'b, 'c> = (
+    Query<'a, (Entity, &'b GravityScale)>,
+    RemovedComponents<'a, GravityScale>,
+    Query<'a, (&'c GlobalTransform, &'c GravityField)>,
+);
+
+/// A component scaling the gravity applied to the dynamic rigid-body of the same entity.
+///
+/// This scales both the global `RapierConfiguration::gravity` and the `GravityField`s
+/// affecting the rigid-body. A scale of zero makes the rigid-body ignore gravity. Removing this
+/// component resets the gravity scale of the rigid-body to 1.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GravityScale(pub Real);
+
+impl Default for GravityScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// The way a `GravityField` computes the acceleration of the rigid-bodies it affects.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GravityFieldKind {
+    /// A constant acceleration, expressed in the local frame of the field's entity.
+    Directional(Vector<Real>),
+    /// An acceleration with a constant magnitude, directed toward the origin of the field's
+    /// entity. A negative magnitude pushes the rigid-bodies away.
+    Radial(Real),
+    /// An acceleration directed toward the origin of the field's entity, with a magnitude equal
+    /// to `strength / distance²`. A negative strength pushes the rigid-bodies away.
+    InverseSquare {
+        /// The strength of the attractor, i.e., the acceleration at a distance of 1.
+        strength: Real,
+        /// The distance below which the magnitude of the acceleration stops increasing.
+        min_distance: Real,
+    },
+}
+
+/// A component adding a custom gravity to the dynamic rigid-bodies located inside of its region.
+///
+/// The field is located at the `GlobalTransform` of its entity. The accelerations of all the
+/// fields containing the center of mass of a rigid-body are added together, and scaled by its
+/// gravity scale. They are added to the global `RapierConfiguration::gravity`, which is
+/// typically set to zero for planetoid gameplay.
+#[derive(Clone)]
+pub struct GravityField {
+    /// How the acceleration is computed.
+    pub kind: GravityFieldKind,
+    /// The region affected by this field, in the local frame of the entity. If `None`, the
+    /// field affects all the rigid-bodies.
+    pub region: Option<ColliderShape>,
+}
+
+impl GravityField {
+    /// A field with a constant acceleration expressed in the local frame of the entity.
+    pub fn directional(acceleration: Vector<Real>) -> Self {
+        Self {
+            kind: GravityFieldKind::Directional(acceleration),
+            region: None,
+        }
+    }
+
+    /// A field attracting toward the origin of the entity with a constant acceleration magnitude.
+    pub fn radial(magnitude: Real) -> Self {
+        Self {
+            kind: GravityFieldKind::Radial(magnitude),
+            region: None,
+        }
+    }
+
+    /// A field attracting toward the origin of the entity with an acceleration magnitude
+    /// decreasing with the square of the distance.
+    pub fn inverse_square(strength: Real) -> Self {
+        Self {
+            kind: GravityFieldKind::InverseSquare {
+                strength,
+                min_distance: 1.0e-3,
+            },
+            region: None,
+        }
+    }
+
+    /// Sets the region affected by this field, in the local frame of the entity.
+    pub fn region(mut self, region: ColliderShape) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// The acceleration applied by this field, located at `field_pos`, at the given world-space point.
+    pub fn acceleration_at_point(
+        &self,
+        field_pos: &Isometry<Real>,
+        point: &Point<Real>,
+    ) -> Vector<Real> {
+        if let Some(region) = &self.region {
+            if !region.contains_point(field_pos, point) {
+                return Vector::zeros();
+            }
+        }
+
+        let center = Point::from(field_pos.translation.vector);
+        let to_center = center - point;
+        let distance = to_center.norm();
+
+        match self.kind {
+            GravityFieldKind::Directional(acceleration) => field_pos * acceleration,
+            GravityFieldKind::Radial(magnitude) => {
+                if distance > 0.0 {
+                    to_center * (magnitude / distance)
+                } else {
+                    Vector::zeros()
+                }
+            }
+            GravityFieldKind::InverseSquare {
+                strength,
+                min_distance,
+            } => {
+                if distance > 0.0 {
+                    let clamped = distance.max(min_distance);
+                    to_center * (strength / (clamped * clamped * distance))
+                } else {
+                    Vector::zeros()
+                }
+            }
+        }
+    }
+}
+
+fn set_gravity_scale(
+    islands: &mut IslandManager,
+    bodies: &mut RigidBodyComponentsSet,
+    handle: Index,
+    gravity_scale: Real,
+) {
+    let rb_forces: Option<&RigidBodyForces> = bodies.get(handle);
+
+    if rb_forces.map(|f| f.gravity_scale) != Some(gravity_scale) {
+        // The body has to be woken-up so it starts falling.
+        if wake_up_dynamic_body(islands, bodies, handle).is_some() {
+            bodies.map_mut_internal(handle, |rb_forces: &mut RigidBodyForces| {
+                rb_forces.gravity_scale = gravity_scale
+            });
+        }
+    }
+}
+
+/// Resets the gravity scale of the rigid-bodies whose `GravityScale` was removed.
+///
+/// Removals are only reported during the frame they happen, so this is called at each frame,
+/// even if no timestep is performed.
+pub(crate) fn reset_removed_gravity_scales(
+    islands: &mut IslandManager,
+    bodies: &mut RigidBodyComponentsSet,
+    gravity_scales: &Query<(Entity, &GravityScale)>,
+    removed_gravity_scales: &RemovedComponents<GravityScale>,
+) {
+    for entity in removed_gravity_scales.iter() {
+        if gravity_scales.get(entity).is_err() {
+            set_gravity_scale(islands, bodies, entity.handle(), 1.0);
+        }
+    }
+}
+
+/// Copies the `GravityScale`s into the forces of their rigid-bodies.
+pub(crate) fn apply_gravity_scales(
+    islands: &mut IslandManager,
+    bodies: &mut RigidBodyComponentsSet,
+    gravity_scales: &Query<(Entity, &GravityScale)>,
+) {
+    for (entity, gravity_scale) in gravity_scales.iter() {
+        set_gravity_scale(islands, bodies, entity.handle(), gravity_scale.0);
+    }
+}
+
+/// Adds the forces applied by the `GravityField`s to the awake dynamic rigid-bodies.
+pub(crate) fn apply_gravity_fields(
+    islands: &IslandManager,
+    bodies: &mut RigidBodyComponentsSet,
+    fields: &[(Isometry<Real>, &GravityField)],
+) {
+    if fields.is_empty() {
+        return;
+    }
+
+    for handle in islands.active_dynamic_bodies() {
+        let rb_mprops: &RigidBodyMassProps = bodies.index(handle.0);
+        let world_com = rb_mprops.world_com;
+        let mass = rb_mprops.mass();
+
+        let acceleration: Vector<Real> = fields
+            .iter()
+            .map(|(field_pos, field)| field.acceleration_at_point(field_pos, &world_com))
+            .sum();
+
+        if acceleration != Vector::zeros() {
+            bodies.map_mut_internal(handle.0, |rb_forces: &mut RigidBodyForces| {
+                rb_forces.force += acceleration * rb_forces.gravity_scale * mass
+            });
+        }
+    }
+}
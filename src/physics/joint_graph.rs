@@ -0,0 +1,171 @@
+use crate::physics::{IntoEntity, IntoHandle, JointsEntityMap};
+use crate::rapier::dynamics::{JointHandle, JointSet, RigidBodyHandle};
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// A set of rigid-bodies connected, directly or transitively, by joints.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JointedAssembly {
+    /// The entities of the rigid-bodies of the assembly.
+    pub bodies: Vec<Entity>,
+    /// The entities of the joints connecting the rigid-bodies of the assembly, i.e., the
+    /// entities with their `JointHandleComponent` or `Joints` component.
+    ///
+    /// An entity with a `Joints` component is listed once, even if several of its joints
+    /// belong to the assembly.
+    pub joints: Vec<Entity>,
+}
+
+/// The graph of the rigid-bodies connected by joints.
+///
+/// This is a snapshot of the `JointSet` and `JointsEntityMap` resources, built with
+/// `JointGraph::new`, to find the rigid-bodies connected to a rigid-body, e.g., to pick up,
+/// break apart, or despawn a whole contraption.
+pub struct JointGraph {
+    /// The rigid-bodies attached to each rigid-body, with the joint attaching them.
+    edges: HashMap<RigidBodyHandle, Vec<(RigidBodyHandle, JointHandle)>>,
+    /// The entity of each joint.
+    entities: HashMap<JointHandle, Entity>,
+}
+
+impl JointGraph {
+    /// Builds the graph of the joints of the given `JointSet`.
+    pub fn new(joints: &JointSet, joints_entity_map: &JointsEntityMap) -> Self {
+        let mut edges: HashMap<_, Vec<_>> = HashMap::new();
+
+        for (handle, joint) in joints.iter() {
+            edges
+                .entry(joint.body1)
+                .or_default()
+                .push((joint.body2, handle));
+            edges
+                .entry(joint.body2)
+                .or_default()
+                .push((joint.body1, handle));
+        }
+
+        let single_joints = joints_entity_map
+            .joints
+            .iter()
+            .map(|(entity, handle)| (*handle, *entity));
+        let joint_lists = joints_entity_map
+            .joint_lists
+            .iter()
+            .flat_map(|(entity, handles)| handles.iter().map(move |handle| (*handle, *entity)));
+        let entities = single_joints.chain(joint_lists).collect();
+
+        Self { edges, entities }
+    }
+
+    /// The entity of the given joint, if it was created by this plugin.
+    pub fn joint_entity(&self, joint: JointHandle) -> Option<Entity> {
+        self.entities.get(&joint).copied()
+    }
+
+    /// The rigid-bodies directly attached to the rigid-body of `body`, with the handles of the
+    /// joints attaching them.
+    pub fn attached(&self, body: Entity) -> impl Iterator<Item = (Entity, JointHandle)> + '_ {
+        let handle: RigidBodyHandle = body.handle();
+        self.edges
+            .get(&handle)
+            .into_iter()
+            .flat_map(|edges| edges.iter())
+            .map(|(other, joint)| (other.0.entity(), *joint))
+    }
+
+    /// The rigid-bodies connected to the rigid-body of `body`, directly or transitively, by
+    /// joints, including `body` itself.
+    ///
+    /// The rigid-bodies are sorted by increasing number of joints from `body`.
+    pub fn assembly(&self, body: Entity) -> JointedAssembly {
+        let mut visited = HashSet::new();
+        self.visit_assembly(body.handle(), &mut visited)
+    }
+
+    /// All the assemblies of at least two rigid-bodies connected by joints.
+    pub fn assemblies(&self) -> Vec<JointedAssembly> {
+        let mut visited = HashSet::new();
+        let mut assemblies = vec![];
+
+        for body in self.edges.keys() {
+            if !visited.contains(body) {
+                assemblies.push(self.visit_assembly(*body, &mut visited));
+            }
+        }
+
+        assemblies
+    }
+
+    /// The entities of the joints along the shortest path (in number of joints) from the
+    /// rigid-body of `from` to the rigid-body of `to`.
+    ///
+    /// Returns `None` if the two rigid-bodies are not connected by joints. Joints without
+    /// entity are skipped.
+    pub fn path(&self, from: Entity, to: Entity) -> Option<Vec<Entity>> {
+        let from: RigidBodyHandle = from.handle();
+        let to: RigidBodyHandle = to.handle();
+        // The joint, and the previous rigid-body, through which each rigid-body is reached.
+        let mut reached_by = HashMap::new();
+        let mut queue = VecDeque::new();
+        reached_by.insert(from, None);
+        queue.push_back(from);
+
+        while let Some(body) = queue.pop_front() {
+            if body == to {
+                let mut path = vec![];
+                let mut current = to;
+
+                while let Some(Some((previous, joint))) = reached_by.get(&current) {
+                    path.extend(self.joint_entity(*joint));
+                    current = *previous;
+                }
+
+                path.reverse();
+                return Some(path);
+            }
+
+            for (other, joint) in self.edges.get(&body).into_iter().flatten() {
+                if !reached_by.contains_key(other) {
+                    reached_by.insert(*other, Some((body, *joint)));
+                    queue.push_back(*other);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Visits the rigid-bodies connected to `start` in breadth-first order.
+    fn visit_assembly(
+        &self,
+        start: RigidBodyHandle,
+        visited: &mut HashSet<RigidBodyHandle>,
+    ) -> JointedAssembly {
+        let mut assembly = JointedAssembly::default();
+        let mut visited_joints = HashSet::new();
+        let mut joint_entities = HashSet::new();
+        let mut queue = VecDeque::new();
+        visited.insert(start);
+        queue.push_back(start);
+
+        while let Some(body) = queue.pop_front() {
+            assembly.bodies.push(body.0.entity());
+
+            for (other, joint) in self.edges.get(&body).into_iter().flatten() {
+                if visited_joints.insert(*joint) {
+                    if let Some(entity) = self.joint_entity(*joint) {
+                        if joint_entities.insert(entity) {
+                            assembly.joints.push(entity);
+                        }
+                    }
+                }
+
+                if visited.insert(*other) {
+                    queue.push_back(*other);
+                }
+            }
+        }
+
+        assembly
+    }
+}
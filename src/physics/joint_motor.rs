@@ -0,0 +1,215 @@
+use crate::physics::{JointHandleComponent, JointParamsComponent};
+use crate::rapier::dynamics::{
+    IntegrationParameters, JointParams, JointSet, RigidBodyActivation, SpringModel,
+};
+#[cfg(feature = "dim3")]
+use crate::rapier::math::Vector;
+use crate::rapier::math::{Real, Rotation};
+use bevy::prelude::*;
+
+/// The joints whose `JointMotor` must be applied again to the `JointSet` resource.
+pub type ChangedJointMotorsQuery<'a, 'b> = Query<
+    'a,
+    (&'b JointHandleComponent, &'b JointMotor),
+    Or<(Changed<JointMotor>, Changed<JointParamsComponent>)>,
+>;
+
+/// A component driving the motor of the joint of the same entity.
+///
+/// This component must be added to an entity with a `JointBuilderComponent` or a
+/// `JointHandleComponent`. It is applied to the joint of the `JointSet` resource whenever it
+/// is modified, which wakes up the rigid-bodies attached to the joint. Motors are supported by
+/// revolute, prismatic, and ball joints. Fixed joints ignore this component.
+///
+/// The motor pulls the joint toward `target_position` with a force proportional to
+/// `stiffness`, and toward `target_velocity` with a force proportional to `damping`. Setting
+/// `stiffness` to zero gives a velocity motor, e.g., for wheels or turrets, while a non-zero
+/// `stiffness` gives a position motor, e.g., for doors or robotic arms. As these forces are
+/// explicit, coefficients too large compared to the mass (or angular inertia) of the
+/// rigid-bodies make the motor overshoot its target, and eventually diverge.
+#[derive(Copy, Clone, Debug)]
+pub struct JointMotor {
+    /// The target position of the joint: a translation along the axis for prismatic joints,
+    /// and an angle, in radians, for revolute and ball joints.
+    pub target_position: Real,
+    /// The target velocity of the joint, along or around the same axis as `target_position`.
+    pub target_velocity: Real,
+    /// The stiffness of the motor, pulling the joint toward its target position.
+    pub stiffness: Real,
+    /// The damping of the motor, pulling the joint toward its target velocity.
+    pub damping: Real,
+    /// The maximum force (or torque) the motor can apply.
+    ///
+    /// It is converted to the maximum impulse of the motor before each timestep, with the
+    /// length of that timestep.
+    pub max_force: Real,
+    /// How the stiffness and damping are interpreted by Rapier.
+    ///
+    /// Defaults to `SpringModel::ForceBased`, where they are the coefficients of a force, unlike
+    /// the `SpringModel::VelocityBased` default of Rapier's joints, where they are the ratios of
+    /// the errors corrected at each timestep.
+    pub model: SpringModel,
+    /// The axis of rotation of the motor of a ball joint, in the local frame of the first
+    /// rigid-body attached to the joint.
+    #[cfg(feature = "dim3")]
+    pub ball_axis: Vector<Real>,
+}
+
+impl Default for JointMotor {
+    fn default() -> Self {
+        Self {
+            target_position: 0.0,
+            target_velocity: 0.0,
+            stiffness: 0.0,
+            damping: 0.0,
+            max_force: Real::MAX,
+            model: SpringModel::ForceBased,
+            #[cfg(feature = "dim3")]
+            ball_axis: Vector::y(),
+        }
+    }
+}
+
+impl JointMotor {
+    /// A motor reaching the given velocity, with the given damping.
+    pub fn velocity(target_velocity: Real, damping: Real) -> Self {
+        Self {
+            target_velocity,
+            damping,
+            ..Default::default()
+        }
+    }
+
+    /// A motor reaching the given position, with the given stiffness and damping.
+    pub fn position(target_position: Real, stiffness: Real, damping: Real) -> Self {
+        Self {
+            target_position,
+            stiffness,
+            damping,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the maximum force (or torque) the motor can apply.
+    pub fn max_force(mut self, max_force: Real) -> Self {
+        self.max_force = max_force;
+        self
+    }
+
+    /// Sets the axis of rotation of the motor of a ball joint.
+    #[cfg(feature = "dim3")]
+    pub fn ball_axis(mut self, axis: Vector<Real>) -> Self {
+        self.ball_axis = axis;
+        self
+    }
+
+    /// Configures the motor of the given joint parameters.
+    ///
+    /// Rapier limits the impulse applied by the motor at each timestep, so `max_force` is
+    /// converted to an impulse with the given timestep length. The plugin converts it again
+    /// before each timestep, since its length may change at each frame.
+    pub fn apply(&self, params: &mut JointParams, dt: Real) {
+        match params {
+            JointParams::BallJoint(joint) => {
+                #[cfg(feature = "dim2")]
+                joint.configure_motor(
+                    Rotation::new(self.target_position),
+                    self.target_velocity,
+                    self.stiffness,
+                    self.damping,
+                );
+                #[cfg(feature = "dim3")]
+                {
+                    let axis = self
+                        .ball_axis
+                        .try_normalize(1.0e-6)
+                        .unwrap_or_else(Vector::y);
+                    joint.configure_motor(
+                        Rotation::from_scaled_axis(axis * self.target_position),
+                        axis * self.target_velocity,
+                        self.stiffness,
+                        self.damping,
+                    );
+                }
+                joint.configure_motor_model(self.model);
+            }
+            JointParams::PrismaticJoint(joint) => {
+                joint.configure_motor(
+                    self.target_position,
+                    self.target_velocity,
+                    self.stiffness,
+                    self.damping,
+                );
+                joint.configure_motor_model(self.model);
+            }
+            #[cfg(feature = "dim3")]
+            JointParams::RevoluteJoint(joint) => {
+                joint.configure_motor(
+                    self.target_position,
+                    self.target_velocity,
+                    self.stiffness,
+                    self.damping,
+                );
+                joint.configure_motor_model(self.model);
+            }
+            JointParams::FixedJoint(_) => {}
+        }
+
+        self.apply_max_force(params, dt);
+    }
+
+    /// Sets the maximum impulse of the motor of the given joint parameters from `max_force`,
+    /// for a timestep of length `dt`.
+    fn apply_max_force(&self, params: &mut JointParams, dt: Real) {
+        let max_impulse = if self.max_force == Real::MAX {
+            Real::MAX
+        } else {
+            self.max_force * dt
+        };
+
+        match params {
+            JointParams::BallJoint(joint) => joint.motor_max_impulse = max_impulse,
+            JointParams::PrismaticJoint(joint) => joint.motor_max_impulse = max_impulse,
+            #[cfg(feature = "dim3")]
+            JointParams::RevoluteJoint(joint) => joint.motor_max_impulse = max_impulse,
+            JointParams::FixedJoint(_) => {}
+        }
+    }
+}
+
+/// Converts the `max_force` of the `JointMotor`s into the maximum impulses of the motors of
+/// their joints, for a timestep of length `dt`.
+pub(crate) fn apply_joint_motor_max_forces(
+    dt: Real,
+    joints: &mut JointSet,
+    joint_motors: &Query<(&JointHandleComponent, &JointMotor)>,
+) {
+    for (handle, motor) in joint_motors.iter() {
+        if let Some(joint) = joints.get_mut(handle.handle()) {
+            motor.apply_max_force(&mut joint.params, dt);
+        }
+    }
+}
+
+/// System responsible for applying the `JointMotor`s to the joints of the `JointSet` resource.
+///
+/// The motors are applied again when the `JointParamsComponent` of their entity is modified,
+/// since these parameters replace the whole joint.
+pub fn sync_joint_motors_system(
+    integration_parameters: Res<IntegrationParameters>,
+    mut joints: ResMut<JointSet>,
+    query: ChangedJointMotorsQuery,
+    mut activations: Query<&mut RigidBodyActivation>,
+) {
+    for (handle, motor) in query.iter() {
+        if let Some(joint) = joints.get_mut(handle.handle()) {
+            motor.apply(&mut joint.params, integration_parameters.dt);
+
+            for entity in [handle.entity1(), handle.entity2()].iter() {
+                if let Ok(mut activation) = activations.get_mut(*entity) {
+                    activation.wake_up(true);
+                }
+            }
+        }
+    }
+}
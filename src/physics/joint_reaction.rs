@@ -0,0 +1,247 @@
+use crate::physics::{JointHandleComponent, Joints, RigidBodyComponentsSet};
+use crate::rapier::data::ComponentSetOption;
+use crate::rapier::dynamics::{Joint, JointParams, JointSet, RigidBodyPosition, RigidBodyType};
+use crate::rapier::math::{AngVector, Real, Vector};
+use crate::rapier::utils::WBasis;
+use bevy::prelude::*;
+
+/// The joints whose `JointReaction`, or `JointReactions`, are updated after each physics step.
+pub type JointReactionsQuery<'a, 'b, 'c> = (
+    Query<'a, (&'b JointHandleComponent, &'c mut JointReaction)>,
+    Query<'a, (&'b Joints, &'c mut JointReactions)>,
+);
+
+/// A component reading back the force and torque applied by the joint of the same entity.
+///
+/// This component must be added to an entity with a `JointBuilderComponent` or a
+/// `JointHandleComponent`. It is updated after each physics step with the impulses applied by
+/// the joint during the last step, divided by the length of that step. The reactions of the
+/// joints of a `Joints` component are read back with a `JointReactions` component instead.
+///
+/// The force and torque are expressed in world-space, and are the ones applied by the joint on
+/// its first rigid-body. The second rigid-body receives the opposite force and torque. The
+/// torque does not include the moment of `force` around the center of mass of the rigid-body.
+///
+/// For 3D prismatic joints, the part of the force orthogonal to the axis assumes the basis
+/// computed by Rapier for a joint created with a zero tangent.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointReaction {
+    /// The force applied by the joint on its first rigid-body.
+    pub force: Vector<Real>,
+    /// The torque applied by the joint on its first rigid-body.
+    pub torque: AngVector<Real>,
+}
+
+impl Default for JointReaction {
+    fn default() -> Self {
+        Self {
+            force: na::zero(),
+            torque: na::zero(),
+        }
+    }
+}
+
+/// A component reading back the forces and torques applied by the joints of the `Joints`
+/// component of the same entity.
+///
+/// It is updated after each physics step with one `JointReaction` per joint of
+/// `Joints::handles`, in the same order. The pending joints have no reaction.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JointReactions(pub Vec<JointReaction>);
+
+impl JointReaction {
+    /// Computes the force and torque applied by the given joint during a step of length `dt`.
+    ///
+    /// Returns `None` if one of the rigid-bodies of the joint does not exist.
+    fn from_joint(joint: &Joint, bodies: &RigidBodyComponentsSet, dt: Real) -> Option<Self> {
+        // Rapier solves the joints attached to a non-dynamic second rigid-body from the
+        // point of view of that rigid-body, so their impulses are applied on it instead of
+        // the first rigid-body.
+        let status2: &RigidBodyType = bodies.get(joint.body2.0)?;
+        let flipped = !status2.is_dynamic();
+        let reference = if flipped { joint.body2 } else { joint.body1 };
+        let pos: &RigidBodyPosition = bodies.get(reference.0)?;
+        let rotation = pos.position.rotation;
+
+        let (impulse, angular_impulse): (Vector<Real>, AngVector<Real>) = match &joint.params {
+            JointParams::BallJoint(joint) => (joint.impulse, joint.motor_impulse),
+            JointParams::FixedJoint(joint) => {
+                #[cfg(feature = "dim2")]
+                {
+                    (
+                        Vector::new(joint.impulse.x, joint.impulse.y),
+                        joint.impulse.z,
+                    )
+                }
+                #[cfg(feature = "dim3")]
+                {
+                    (
+                        joint.impulse.fixed_rows::<3>(0).into_owned(),
+                        joint.impulse.fixed_rows::<3>(3).into_owned(),
+                    )
+                }
+            }
+            JointParams::PrismaticJoint(joint) => {
+                let axis = if flipped {
+                    joint.local_axis2()
+                } else {
+                    joint.local_axis1()
+                };
+                let basis = axis.orthonormal_basis();
+                let axial_impulse = joint.motor_impulse - joint.limits_impulse;
+
+                #[cfg(feature = "dim2")]
+                {
+                    (
+                        rotation * (basis[0] * joint.impulse.x + *axis * axial_impulse),
+                        joint.impulse.y,
+                    )
+                }
+                #[cfg(feature = "dim3")]
+                {
+                    (
+                        rotation
+                            * (basis[0] * joint.impulse[0]
+                                + basis[1] * joint.impulse[1]
+                                + *axis * axial_impulse),
+                        joint.impulse.fixed_rows::<3>(2).into_owned(),
+                    )
+                }
+            }
+            #[cfg(feature = "dim3")]
+            JointParams::RevoluteJoint(joint) => {
+                let (basis, axis) = if flipped {
+                    (joint.basis2, joint.local_axis2)
+                } else {
+                    (joint.basis1, joint.local_axis1)
+                };
+
+                (
+                    joint.impulse.fixed_rows::<3>(0).into_owned(),
+                    rotation
+                        * (basis[0] * joint.impulse[3]
+                            + basis[1] * joint.impulse[4]
+                            + *axis * joint.motor_impulse),
+                )
+            }
+        };
+
+        let sign = if flipped { -1.0 } else { 1.0 };
+
+        Some(Self {
+            force: impulse * (sign / dt),
+            torque: angular_impulse * (sign / dt),
+        })
+    }
+}
+
+/// Updates the `JointReaction` of the joints after a physics step of length `dt`.
+pub(crate) fn update_joint_reactions(
+    dt: Real,
+    joints: &JointSet,
+    bodies: &RigidBodyComponentsSet,
+    (reactions, list_reactions): &mut JointReactionsQuery,
+) {
+    if dt == 0.0 {
+        return;
+    }
+
+    let update = |handle: &JointHandleComponent, reaction: &mut JointReaction| {
+        let new_reaction = joints
+            .get(handle.handle())
+            .and_then(|joint| JointReaction::from_joint(joint, bodies, dt));
+
+        if let Some(new_reaction) = new_reaction {
+            *reaction = new_reaction;
+        }
+    };
+
+    for (handle, mut reaction) in reactions.iter_mut() {
+        update(handle, &mut reaction);
+    }
+
+    for (list, mut list_reactions) in list_reactions.iter_mut() {
+        let handles = list.handles();
+        list_reactions
+            .0
+            .resize(handles.len(), JointReaction::default());
+
+        for (handle, reaction) in handles.iter().zip(list_reactions.0.iter_mut()) {
+            update(handle, reaction);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::{
+        test_app, ColliderBundle, JointBuilderComponent, RapierConfiguration, RigidBodyBundle,
+    };
+    use crate::rapier::dynamics::{BallJoint, RigidBodyMassProps};
+    use crate::rapier::geometry::{ColliderFlags, ColliderShape, InteractionGroups};
+    use crate::rapier::math::Point;
+
+    fn spawn_body(app: &mut App, y: Real, body_type: RigidBodyType) -> Entity {
+        app.world
+            .spawn()
+            .insert_bundle(RigidBodyBundle {
+                body_type,
+                position: (Vector::y() * y).into(),
+                ..Default::default()
+            })
+            .insert_bundle(ColliderBundle {
+                flags: ColliderFlags {
+                    collision_groups: InteractionGroups::none(),
+                    ..Default::default()
+                },
+                shape: ColliderShape::ball(0.5),
+                ..Default::default()
+            })
+            .id()
+    }
+
+    /// The reaction of a ball joint holding a dynamic body below a static one, and the weight
+    /// of the dynamic body.
+    fn hanging_reaction(static_first: bool) -> (JointReaction, Real) {
+        let mut app = test_app();
+        let fixed = spawn_body(&mut app, 5.0, RigidBodyType::Static);
+        let hanging = spawn_body(&mut app, 4.0, RigidBodyType::Dynamic);
+        let (fixed_anchor, hanging_anchor) = (Point::from(-Vector::y()), Point::origin());
+        let builder = if static_first {
+            JointBuilderComponent::new(BallJoint::new(fixed_anchor, hanging_anchor), fixed, hanging)
+        } else {
+            JointBuilderComponent::new(BallJoint::new(hanging_anchor, fixed_anchor), hanging, fixed)
+        };
+        let joint = app
+            .world
+            .spawn()
+            .insert(builder)
+            .insert(JointReaction::default())
+            .id();
+
+        for _ in 0..120 {
+            app.update();
+        }
+
+        let mass = app.world.get::<RigidBodyMassProps>(hanging).unwrap().mass();
+        let gravity = app
+            .world
+            .get_resource::<RapierConfiguration>()
+            .unwrap()
+            .gravity;
+        let reaction = *app.world.get::<JointReaction>(joint).unwrap();
+        (reaction, mass * gravity.y.abs())
+    }
+
+    #[test]
+    fn reaction_is_applied_on_the_first_body() {
+        // The joint pulls the static body down...
+        let (reaction, weight) = hanging_reaction(true);
+        assert!((reaction.force + Vector::y() * weight).norm() < 0.05 * weight);
+
+        // ... and holds the dynamic body up.
+        let (reaction, weight) = hanging_reaction(false);
+        assert!((reaction.force - Vector::y() * weight).norm() < 0.05 * weight);
+    }
+}
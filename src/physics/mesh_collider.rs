@@ -1,14 +1,28 @@
 #[cfg(feature = "dim3")]
+use crate::physics::IntoEntity;
+#[cfg(feature = "dim3")]
+use crate::rapier::dynamics::{RigidBodyMassProps, RigidBodyPosition};
+#[cfg(feature = "dim3")]
+use crate::rapier::geometry::{ColliderMassProps, ColliderParent, ColliderShape};
+#[cfg(feature = "dim3")]
+use crate::rapier::math::{Isometry, Vector};
+#[cfg(feature = "dim3")]
+use crate::rapier::parry::transformation::{
+    vhacd::{VHACDParameters, VHACD},
+    voxelization::FillMode,
+};
+#[cfg(feature = "dim3")]
 use crate::{na::Point3, prelude::Real};
 #[cfg(feature = "dim3")]
 use bevy::{
-    prelude::Mesh,
+    app::{Events, ManualEventReader},
+    prelude::*,
     render::mesh::{Indices, VertexAttributeValues},
 };
 #[cfg(feature = "dim3")]
 use rapier::math::DIM;
 #[cfg(feature = "dim3")]
-use std::{convert::TryFrom, error::Error, fmt};
+use std::{collections::HashSet, convert::TryFrom, error::Error, fmt};
 
 #[cfg(feature = "dim3")]
 #[derive(Debug, Clone, Default)]
@@ -50,63 +64,402 @@ impl fmt::Display for VertexIndicesMissing {
 #[cfg(feature = "dim3")]
 
 impl Error for VertexIndicesMissing {}
+
+#[cfg(feature = "dim3")]
+#[derive(Debug, Clone, Default)]
+pub struct ConvexHullFailed();
+
+#[cfg(feature = "dim3")]
+impl fmt::Display for ConvexHullFailed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Failed to compute the convex hull of the vertices.")
+    }
+}
+
+#[cfg(feature = "dim3")]
+impl Error for ConvexHullFailed {}
+
+#[cfg(feature = "dim3")]
+#[derive(Debug, Clone, Default)]
+pub struct ConvexDecompositionFailed();
+
+#[cfg(feature = "dim3")]
+impl fmt::Display for ConvexDecompositionFailed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "The convex decomposition of the mesh has no convex part."
+        )
+    }
+}
+
+#[cfg(feature = "dim3")]
+impl Error for ConvexDecompositionFailed {}
+
 #[cfg(feature = "dim3")]
 #[derive(Debug, Clone)]
 pub enum ErrorSum {
     VertexFormatError(VertexFormatError),
     VertexPositionAttributeMissing(VertexPositionAttributeMissing),
     VertexIndicesMissing(VertexIndicesMissing),
+    ConvexHullFailed(ConvexHullFailed),
+    ConvexDecompositionFailed(ConvexDecompositionFailed),
 }
-#[cfg(feature = "dim3")]
 
-impl Error for ErrorSum {}
 #[cfg(feature = "dim3")]
+impl Error for ErrorSum {}
 
+#[cfg(feature = "dim3")]
 impl fmt::Display for ErrorSum {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self {
             ErrorSum::VertexFormatError(e) => e.fmt(f),
             ErrorSum::VertexPositionAttributeMissing(e) => e.fmt(f),
             ErrorSum::VertexIndicesMissing(e) => e.fmt(f),
+            ErrorSum::ConvexHullFailed(e) => e.fmt(f),
+            ErrorSum::ConvexDecompositionFailed(e) => e.fmt(f),
         }
     }
 }
-#[cfg(feature = "dim3")]
 
+#[cfg(feature = "dim3")]
 pub struct SharedShapeMesh(pub Mesh);
 
+#[cfg(feature = "dim3")]
+impl SharedShapeMesh {
+    /// Builds a compound of convex parts approximating the mesh, using the approximate convex
+    /// decomposition (VHACD) with the given parameters.
+    ///
+    /// Unlike a trimesh, the resulting shape has a volume, so it can be used by the colliders
+    /// of dynamic rigid-bodies.
+    pub fn convex_decomposition(
+        &self,
+        params: &ConvexDecompositionParams,
+    ) -> Result<ColliderShape, ErrorSum> {
+        let (vertices, indices) = mesh_buffers(&self.0)?;
+        convex_decomposition(&vertices, &indices, params)
+    }
+}
+
 #[cfg(feature = "dim3")]
 // Easy conversion from Bevy Mesh to types used for building SharedShape
 impl TryFrom<SharedShapeMesh> for (Vec<Point3<Real>>, Vec<[u32; DIM]>) {
     fn try_from(
         mesh: SharedShapeMesh,
     ) -> Result<(Vec<Point3<Real>>, Vec<[u32; DIM]>), Self::Error> {
-        let vertices = mesh.0.attribute(Mesh::ATTRIBUTE_POSITION);
-        let indices = mesh.0.indices();
-
-        let vtx: Vec<_> =
-            match vertices.ok_or(ErrorSum::VertexPositionAttributeMissing(Default::default()))? {
-                VertexAttributeValues::Float(vtx) => Ok(vtx
-                    .chunks(3)
-                    .map(|v| Point3::from([v[0] as Real, v[1] as Real, v[2] as Real]))
-                    .collect()),
-                VertexAttributeValues::Float3(vtx) => Ok(vtx
-                    .iter()
-                    .map(|v| Point3::from([v[0] as Real, v[1] as Real, v[2] as Real]))
-                    .collect()),
-                _ => Err(ErrorSum::VertexFormatError(Default::default())),
-            }?;
-
-        let idx = match indices.ok_or(ErrorSum::VertexIndicesMissing(Default::default()))? {
-            Indices::U16(idx) => idx
-                .chunks_exact(3)
-                .map(|i| [i[0] as u32, i[1] as u32, i[2] as u32])
-                .collect(),
-            Indices::U32(idx) => idx.chunks_exact(3).map(|i| [i[0], i[1], i[2]]).collect(),
-        };
-
-        Ok((vtx, idx))
+        mesh_buffers(&mesh.0)
     }
 
     type Error = ErrorSum;
 }
+
+#[cfg(feature = "dim3")]
+type MeshBuffers = (Vec<Point3<Real>>, Vec<[u32; DIM]>);
+
+#[cfg(feature = "dim3")]
+// The vertices and triangles of a Bevy Mesh, as used for building SharedShape
+fn mesh_buffers(mesh: &Mesh) -> Result<MeshBuffers, ErrorSum> {
+    let vertices = mesh.attribute(Mesh::ATTRIBUTE_POSITION);
+    let indices = mesh.indices();
+
+    let vtx: Vec<_> =
+        match vertices.ok_or(ErrorSum::VertexPositionAttributeMissing(Default::default()))? {
+            VertexAttributeValues::Float(vtx) => Ok(vtx
+                .chunks(3)
+                .map(|v| Point3::from([v[0] as Real, v[1] as Real, v[2] as Real]))
+                .collect()),
+            VertexAttributeValues::Float3(vtx) => Ok(vtx
+                .iter()
+                .map(|v| Point3::from([v[0] as Real, v[1] as Real, v[2] as Real]))
+                .collect()),
+            _ => Err(ErrorSum::VertexFormatError(Default::default())),
+        }?;
+
+    let idx = match indices.ok_or(ErrorSum::VertexIndicesMissing(Default::default()))? {
+        Indices::U16(idx) => idx
+            .chunks_exact(3)
+            .map(|i| [i[0] as u32, i[1] as u32, i[2] as u32])
+            .collect(),
+        Indices::U32(idx) => idx.chunks_exact(3).map(|i| [i[0], i[1], i[2]]).collect(),
+    };
+
+    Ok((vtx, idx))
+}
+
+#[cfg(feature = "dim3")]
+/// The kind of shape built from the mesh of an `AsyncCollider`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MeshColliderShape {
+    /// A triangle mesh with the triangles of the mesh.
+    TriMesh,
+    /// The convex hull of the vertices of the mesh.
+    ConvexHull,
+    /// A compound of convex parts approximating the mesh, computed by the approximate convex
+    /// decomposition with the given parameters.
+    ///
+    /// The decomposition isn't run on a background task: it blocks the system building the
+    /// shape until it is complete.
+    ConvexDecomposition(ConvexDecompositionParams),
+}
+
+#[cfg(feature = "dim3")]
+impl MeshColliderShape {
+    /// Builds a shape of this kind from the given mesh.
+    pub fn build(&self, mesh: &Mesh) -> Result<ColliderShape, ErrorSum> {
+        self.build_scaled(mesh, &Vector::repeat(1.0))
+    }
+
+    /// Builds a shape of this kind from the given mesh, with its vertices multiplied
+    /// component-wise by `scale`.
+    pub fn build_scaled(
+        &self,
+        mesh: &Mesh,
+        scale: &Vector<Real>,
+    ) -> Result<ColliderShape, ErrorSum> {
+        let (mut vertices, indices) = mesh_buffers(mesh)?;
+        for vertex in &mut vertices {
+            vertex.coords.component_mul_assign(scale);
+        }
+
+        match self {
+            MeshColliderShape::TriMesh => Ok(ColliderShape::trimesh(vertices, indices)),
+            MeshColliderShape::ConvexHull => ColliderShape::convex_hull(&vertices)
+                .ok_or(ErrorSum::ConvexHullFailed(Default::default())),
+            MeshColliderShape::ConvexDecomposition(params) => {
+                convex_decomposition(&vertices, &indices, params)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "dim3")]
+/// The parameters of the approximate convex decomposition (VHACD) of a mesh.
+///
+/// See https://github.com/Unity-Technologies/VHACD#parameters for details. Lower resolutions
+/// and higher concavities are faster, but approximate the mesh with fewer, coarser, convex
+/// parts.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ConvexDecompositionParams {
+    /// The maximum concavity of each part, in `[0.0, 1.0]`.
+    pub concavity: Real,
+    /// The bias toward clipping along symmetry planes, in `[0.0, 1.0]`.
+    pub alpha: Real,
+    /// The bias toward clipping along revolution planes, in `[0.0, 1.0]`.
+    pub beta: Real,
+    /// The resolution of the voxelization of the mesh.
+    pub resolution: u32,
+    /// The granularity of the search for the best clipping plane.
+    pub plane_downsampling: u32,
+    /// The precision of the convex hulls computed while searching for the best clipping plane.
+    pub convex_hull_downsampling: u32,
+    /// Whether the holes inside of the mesh are detected while voxelizing it. The mesh is
+    /// considered to be solid otherwise.
+    pub detect_cavities: bool,
+    /// Whether the convex hulls are approximated during the decomposition, which is faster,
+    /// with a slight degradation of the decomposition quality.
+    pub convex_hull_approximation: bool,
+    /// The maximum number of convex parts.
+    pub max_convex_hulls: u32,
+}
+
+#[cfg(feature = "dim3")]
+impl Default for ConvexDecompositionParams {
+    fn default() -> Self {
+        let params = VHACDParameters::default();
+        Self {
+            concavity: params.concavity,
+            alpha: params.alpha,
+            beta: params.beta,
+            resolution: params.resolution,
+            plane_downsampling: params.plane_downsampling,
+            convex_hull_downsampling: params.convex_hull_downsampling,
+            detect_cavities: false,
+            convex_hull_approximation: params.convex_hull_approximation,
+            max_convex_hulls: params.max_convex_hulls,
+        }
+    }
+}
+
+#[cfg(feature = "dim3")]
+impl From<&ConvexDecompositionParams> for VHACDParameters {
+    fn from(params: &ConvexDecompositionParams) -> Self {
+        Self {
+            concavity: params.concavity,
+            alpha: params.alpha,
+            beta: params.beta,
+            resolution: params.resolution,
+            plane_downsampling: params.plane_downsampling,
+            convex_hull_downsampling: params.convex_hull_downsampling,
+            fill_mode: FillMode::FloodFill {
+                detect_cavities: params.detect_cavities,
+            },
+            convex_hull_approximation: params.convex_hull_approximation,
+            max_convex_hulls: params.max_convex_hulls,
+        }
+    }
+}
+
+#[cfg(feature = "dim3")]
+// The compound of the convex parts of the given trimesh, without panicking on degenerate parts
+fn convex_decomposition(
+    vertices: &[Point3<Real>],
+    indices: &[[u32; DIM]],
+    params: &ConvexDecompositionParams,
+) -> Result<ColliderShape, ErrorSum> {
+    if vertices.is_empty() || indices.is_empty() {
+        return Err(ErrorSum::ConvexDecompositionFailed(Default::default()));
+    }
+
+    let decomposition = VHACD::decompose(&params.into(), vertices, indices, true);
+    let parts: Vec<_> = decomposition
+        .compute_exact_convex_hulls(vertices, indices)
+        .into_iter()
+        // Skip the degenerate parts, which make `ColliderShape::convex_mesh` panic.
+        .filter(|(vertices, indices)| vertices.len() >= 4 && !indices.is_empty())
+        .filter_map(|(vertices, indices)| ColliderShape::convex_mesh(vertices, &indices))
+        .map(|part| (Isometry::identity(), part))
+        .collect();
+
+    if parts.is_empty() {
+        Err(ErrorSum::ConvexDecompositionFailed(Default::default()))
+    } else {
+        Ok(ColliderShape::compound(parts))
+    }
+}
+
+#[cfg(feature = "dim3")]
+pub type AsyncCollidersQuery<'a, 'b, 'c> = Query<
+    'a,
+    (
+        Entity,
+        &'b AsyncCollider,
+        ChangeTrackers<AsyncCollider>,
+        Option<&'c mut ColliderShape>,
+        Option<(&'b ColliderParent, &'b ColliderMassProps)>,
+    ),
+>;
+
+#[cfg(feature = "dim3")]
+/// A component building the `ColliderShape` of its entity from a mesh asset, once it is loaded.
+///
+/// The entity is a collider like any other, except that it can be spawned without
+/// `ColliderShape`: it is attached to its rigid-body once the shape is built. If it already has
+/// a `ColliderShape`, e.g., as part of a `ColliderBundle`, that shape is used until the mesh is
+/// loaded. The shape is built again when the mesh asset is modified, or when this component
+/// is modified, and the mass properties of the rigid-body are updated accordingly.
+///
+/// The vertices of the mesh are multiplied by `scale`, e.g., `1.0 / RapierConfiguration::scale`
+/// to build the shape of a mesh expressed in the units of the rendering. The mesh must have a
+/// `Float3` position attribute and indices. The shape is left unchanged,
+/// and an `AsyncColliderError` event is sent, if it can't be built from the mesh.
+///
+/// The shapes are built synchronously, in the `PhysicsStages::FinalizeCreations` stage, during
+/// the frame the mesh is loaded. The convex decomposition of a large mesh can stall that frame.
+#[derive(Clone, Debug)]
+pub struct AsyncCollider {
+    /// The mesh to build the shape from.
+    pub mesh: Handle<Mesh>,
+    /// The kind of shape to build.
+    pub shape: MeshColliderShape,
+    /// The component-wise scale of the vertices of the mesh.
+    pub scale: Vector<Real>,
+}
+
+#[cfg(feature = "dim3")]
+impl AsyncCollider {
+    /// A collider with a shape of the given kind built from the given mesh.
+    pub fn new(mesh: Handle<Mesh>, shape: MeshColliderShape) -> Self {
+        Self {
+            mesh,
+            shape,
+            scale: Vector::repeat(1.0),
+        }
+    }
+
+    /// Sets the component-wise scale of the vertices of the mesh.
+    pub fn scale(mut self, scale: Vector<Real>) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+#[cfg(feature = "dim3")]
+/// An event sent when the shape of an `AsyncCollider` can't be built from its mesh.
+#[derive(Clone, Debug)]
+pub struct AsyncColliderError {
+    /// The entity with the `AsyncCollider`.
+    pub entity: Entity,
+    /// The reason of the error.
+    pub error: ErrorSum,
+}
+
+#[cfg(feature = "dim3")]
+/// System responsible for building the shapes of the `AsyncCollider`s from their meshes.
+pub fn create_async_colliders_system(
+    mut commands: Commands,
+    meshes: Option<Res<Assets<Mesh>>>,
+    mesh_events: Option<Res<Events<AssetEvent<Mesh>>>>,
+    mut mesh_events_reader: Local<ManualEventReader<AssetEvent<Mesh>>>,
+    mut colliders: AsyncCollidersQuery,
+    mut bodies: Query<(&mut RigidBodyMassProps, &RigidBodyPosition)>,
+    mut errors: EventWriter<AsyncColliderError>,
+) {
+    // There is nothing to build without the mesh assets, e.g., in headless apps.
+    let (meshes, mesh_events) = match (meshes, mesh_events) {
+        (Some(meshes), Some(mesh_events)) => (meshes, mesh_events),
+        _ => return,
+    };
+    let mut loaded_meshes = HashSet::new();
+
+    for event in mesh_events_reader.iter(&mesh_events) {
+        match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                loaded_meshes.insert(handle.clone());
+            }
+            AssetEvent::Removed { .. } => {}
+        }
+    }
+
+    for (entity, collider, tracker, co_shape, co_parent) in colliders.iter_mut() {
+        if !tracker.is_changed() && !loaded_meshes.contains(&collider.mesh) {
+            continue;
+        }
+
+        // Wait for the mesh to be loaded.
+        let new_shape = match meshes.get(&collider.mesh) {
+            Some(mesh) => collider.shape.build_scaled(mesh, &collider.scale),
+            None => continue,
+        };
+
+        let new_shape = match new_shape {
+            Ok(new_shape) => new_shape,
+            Err(error) => {
+                errors.send(AsyncColliderError { entity, error });
+                continue;
+            }
+        };
+
+        match co_shape {
+            Some(mut co_shape) => {
+                // The collider is already attached, so update the mass properties of its
+                // rigid-body.
+                if let Some((co_parent, co_mprops)) = co_parent {
+                    if let Ok((mut rb_mprops, rb_pos)) = bodies.get_mut(co_parent.handle.entity()) {
+                        rb_mprops.local_mprops -= co_mprops
+                            .mass_properties(&**co_shape)
+                            .transform_by(&co_parent.pos_wrt_parent);
+                        rb_mprops.local_mprops += co_mprops
+                            .mass_properties(&*new_shape)
+                            .transform_by(&co_parent.pos_wrt_parent);
+                        rb_mprops.update_world_mass_properties(&rb_pos.position);
+                    }
+                }
+
+                *co_shape = new_shape;
+            }
+            None => {
+                commands.entity(entity).insert(new_shape);
+            }
+        }
+    }
+}
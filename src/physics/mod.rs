@@ -1,10 +1,31 @@
+pub use self::aerodynamics::*;
+pub use self::batch_queries::*;
+pub use self::character_controller::*;
 pub use self::collider_component_set::*;
 pub use self::components::*;
+pub use self::explosion::*;
+pub use self::external_forces::*;
+pub use self::fluid_volume::*;
+pub use self::gravity::*;
+pub use self::joint_graph::*;
+pub use self::joint_motor::*;
+pub use self::joint_reaction::*;
 pub use self::mesh_collider::*;
+pub use self::moving_platform::*;
+pub use self::parent_joint::*;
 pub use self::plugins::*;
+pub use self::ragdoll::*;
 pub use self::resources::*;
 pub use self::rigid_body_component_set::*;
+pub use self::rope::*;
+#[cfg(feature = "dim3")]
+pub use self::scene_colliders::*;
+pub use self::sensors::*;
+pub use self::spring::*;
 pub use self::systems::*;
+#[cfg(feature = "dim3")]
+pub use self::vehicle::*;
+pub use self::velocity_limits::*;
 
 use crate::rapier::data::{ComponentSet, ComponentSetMut, ComponentSetOption, Index};
 use crate::rapier::prelude::*;
@@ -205,10 +226,31 @@ impl<'a, 'b, 'c, T: 'static + Send + Sync> ComponentSetMut<T>
     }
 }
 
+mod aerodynamics;
+mod batch_queries;
+mod character_controller;
 mod collider_component_set;
 mod components;
+mod explosion;
+mod external_forces;
+mod fluid_volume;
+mod gravity;
+mod joint_graph;
+mod joint_motor;
+mod joint_reaction;
 mod mesh_collider;
+mod moving_platform;
+mod parent_joint;
 mod plugins;
+mod ragdoll;
 mod resources;
 mod rigid_body_component_set;
+mod rope;
+#[cfg(feature = "dim3")]
+mod scene_colliders;
+mod sensors;
+mod spring;
 mod systems;
+#[cfg(feature = "dim3")]
+mod vehicle;
+mod velocity_limits;
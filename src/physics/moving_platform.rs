@@ -0,0 +1,183 @@
+use crate::physics::{
+    IntoEntity, KinematicCharacterControllerOutput, RapierConfiguration, TimestepMode,
+};
+use crate::rapier::dynamics::{
+    IntegrationParameters, RigidBodyColliders, RigidBodyMassProps, RigidBodyPosition,
+    RigidBodyType, RigidBodyVelocity,
+};
+use crate::rapier::geometry::{ColliderParent, NarrowPhase};
+use crate::rapier::math::{AngVector, Point, Real, Vector};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// A component carrying the bodies and characters resting on top of a kinematic
+/// position-based rigid-body.
+///
+/// The platform is moved by setting the `next_position` of its `RigidBodyPosition`, from
+/// a system running before `PhysicsSystems::UpdateMovingPlatforms`. The resulting velocity
+/// is computed at each frame and transferred to:
+/// - the dynamic rigid-bodies touching the platform from above, detected with the contacts
+///   of the platform's colliders.
+/// - the `KinematicCharacterController`s standing on the platform.
+#[derive(Copy, Clone, Debug)]
+pub struct MovingPlatform {
+    /// The direction pointing upward for this platform.
+    pub up: Vector<Real>,
+    /// The maximum angle (radians) between `up` and a contact normal for a dynamic
+    /// rigid-body to be considered resting on top of the platform.
+    pub max_rider_slope_angle: Real,
+    /// Should the rotation of the platform also rotate the objects it carries around its pivot?
+    pub carry_rotation: bool,
+    /// The linear velocity of the platform's origin computed during the last frame.
+    pub linvel: Vector<Real>,
+    /// The angular velocity of the platform computed during the last frame.
+    pub angvel: AngVector<Real>,
+}
+
+impl Default for MovingPlatform {
+    fn default() -> Self {
+        Self {
+            up: Vector::y(),
+            max_rider_slope_angle: 45.0_f32.to_radians(),
+            carry_rotation: true,
+            linvel: Vector::zeros(),
+            angvel: na::zero(),
+        }
+    }
+}
+
+impl MovingPlatform {
+    fn velocity_at_point(&self, point: &Point<Real>, origin: &Point<Real>) -> Vector<Real> {
+        let velocity = RigidBodyVelocity {
+            linvel: self.linvel,
+            angvel: if self.carry_rotation {
+                self.angvel
+            } else {
+                na::zero()
+            },
+        };
+        velocity.velocity_at_point(point, origin)
+    }
+}
+
+/// System responsible for transferring the motion of the `MovingPlatform`s to the
+/// rigid-bodies and characters they carry.
+pub fn update_moving_platforms_system(
+    (time, configuration, integration_parameters, narrow_phase): (
+        Res<Time>,
+        Res<RapierConfiguration>,
+        Res<IntegrationParameters>,
+        Res<NarrowPhase>,
+    ),
+    collider_parents: Query<&ColliderParent>,
+    mut platforms: Query<(
+        Entity,
+        &mut MovingPlatform,
+        &RigidBodyPosition,
+        &RigidBodyColliders,
+    )>,
+    mut characters: Query<
+        (&KinematicCharacterControllerOutput, &mut RigidBodyPosition),
+        Without<MovingPlatform>,
+    >,
+    mut dynamic_bodies: Query<(&mut RigidBodyVelocity, &RigidBodyMassProps, &RigidBodyType)>,
+) {
+    // The duration of the step moving the platforms to their new position.
+    let dt = match configuration.timestep_mode {
+        TimestepMode::VariableTimestep => time.delta_seconds().min(integration_parameters.dt),
+        _ => integration_parameters.dt,
+    };
+    if dt <= 0.0 {
+        return;
+    }
+
+    let collider_body = |collider: Entity| {
+        collider_parents
+            .get(collider)
+            .map(|co_parent| co_parent.handle.entity())
+            .unwrap_or(collider)
+    };
+    let mut platform_moves = HashMap::new();
+
+    for (entity, mut platform, rb_pos, rb_colliders) in platforms.iter_mut() {
+        let origin = Point::from(rb_pos.position.translation.vector);
+        let prev_platform = *platform;
+        let velocity = rb_pos.interpolate_velocity(1.0 / dt);
+        let new_linvel = velocity.velocity_at_point(&origin, &Point::origin());
+
+        if platform.linvel != new_linvel || platform.angvel != velocity.angvel {
+            platform.linvel = new_linvel;
+            platform.angvel = velocity.angvel;
+        }
+
+        let delta = rb_pos.next_position * rb_pos.position.inverse();
+        let translation =
+            rb_pos.next_position.translation.vector - rb_pos.position.translation.vector;
+        platform_moves.insert(entity, (delta, translation, *platform));
+
+        // Transfer the change of velocity of the platform to the dynamic bodies on top of it.
+        let mut riders = vec![];
+        for co_handle in &rb_colliders.0 {
+            for pair in narrow_phase.contacts_with(*co_handle) {
+                if !pair.has_any_active_contact {
+                    continue;
+                }
+
+                let (other, sign) = if pair.collider1 == *co_handle {
+                    (pair.collider2, 1.0)
+                } else {
+                    (pair.collider1, -1.0)
+                };
+
+                let on_top = pair.manifolds.iter().any(|manifold| {
+                    !manifold.points.is_empty()
+                        && (manifold.data.normal * sign).angle(&platform.up)
+                            <= platform.max_rider_slope_angle
+                });
+
+                let rider = collider_body(other.entity());
+                if on_top && rider != entity && !riders.contains(&rider) {
+                    riders.push(rider);
+                }
+            }
+        }
+
+        for rider in riders {
+            if let Ok((mut rb_vels, rb_mprops, rb_type)) = dynamic_bodies.get_mut(rider) {
+                if !rb_type.is_dynamic() {
+                    continue;
+                }
+
+                let com = rb_mprops.world_com;
+                let delta_linvel = platform.velocity_at_point(&com, &origin)
+                    - prev_platform.velocity_at_point(&com, &origin);
+
+                if delta_linvel != Vector::zeros() {
+                    rb_vels.linvel += delta_linvel;
+                }
+
+                if platform.carry_rotation && platform.angvel != prev_platform.angvel {
+                    rb_vels.angvel += platform.angvel - prev_platform.angvel;
+                }
+            }
+        }
+    }
+
+    // Characters standing on a platform are moved with it.
+    for (output, mut rb_pos) in characters.iter_mut() {
+        let ground_body = match output.ground {
+            Some(ground) => collider_body(ground),
+            None => continue,
+        };
+
+        if let Some((delta, translation, platform)) = platform_moves.get(&ground_body) {
+            if platform.carry_rotation {
+                rb_pos.next_position = delta * rb_pos.next_position;
+            } else {
+                // Only carry the translation of the platform's origin, like for the dynamic
+                // bodies, instead of orbiting its pivot.
+                rb_pos.next_position.translation.vector += translation;
+            }
+        }
+    }
+}
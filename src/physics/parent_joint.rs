@@ -0,0 +1,136 @@
+use crate::physics::JointBuilderComponent;
+#[cfg(feature = "dim3")]
+use crate::rapier::dynamics::RevoluteJoint;
+use crate::rapier::dynamics::{
+    BallJoint, FixedJoint, JointParams, PrismaticJoint, RigidBodyPosition,
+};
+use crate::rapier::math::{Isometry, Point, Real, Vector};
+#[cfg(feature = "dim3")]
+use crate::rapier::utils::WBasis;
+use bevy::prelude::*;
+use na::Unit;
+
+/// The kind of joint created by a `JointToParent` component.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ParentJointKind {
+    /// A fixed joint, locking the relative position of the rigid-bodies.
+    Fixed,
+    /// A ball joint, letting the child rigid-body rotate freely around its origin.
+    Ball,
+    /// A prismatic joint, letting the child rigid-body translate along the given axis,
+    /// expressed in its local frame.
+    Prismatic(Unit<Vector<Real>>),
+    /// A revolute joint, letting the child rigid-body rotate around the given axis, expressed
+    /// in its local frame, and passing through its origin.
+    #[cfg(feature = "dim3")]
+    Revolute(Unit<Vector<Real>>),
+}
+
+/// A component creating a joint between the rigid-body of its entity and the rigid-body of
+/// its closest ancestor, in the Bevy hierarchy, that also has a rigid-body.
+///
+/// By default, the rigid-body of an entity is independent from the rigid-bodies of its
+/// ancestors. This component makes it possible to describe articulated objects as entity
+/// hierarchies, e.g., in prefabs. The anchors of the joint are computed from the relative
+/// position of the two rigid-bodies, so the joint holds them where they are spawned.
+///
+/// Once both rigid-bodies exist, this component is replaced by a `JointBuilderComponent` on
+/// the same entity, which is then replaced by the `JointHandleComponent` of the joint.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointToParent {
+    /// The kind of joint to create.
+    pub kind: ParentJointKind,
+}
+
+impl Default for JointToParent {
+    fn default() -> Self {
+        Self {
+            kind: ParentJointKind::Fixed,
+        }
+    }
+}
+
+impl JointToParent {
+    /// A joint of the given kind with the closest ancestor rigid-body.
+    pub fn new(kind: ParentJointKind) -> Self {
+        Self { kind }
+    }
+
+    /// The parameters of the joint, given the position of the child rigid-body relative to its
+    /// parent rigid-body.
+    fn params(&self, relative: &Isometry<Real>) -> JointParams {
+        let anchor1 = Point::from(relative.translation.vector);
+        let anchor2 = Point::origin();
+
+        match self.kind {
+            ParentJointKind::Fixed => FixedJoint::new(*relative, Isometry::identity()).into(),
+            ParentJointKind::Ball => BallJoint::new(anchor1, anchor2).into(),
+            ParentJointKind::Prismatic(axis) => {
+                let axis1 = relative.rotation * axis;
+
+                #[cfg(feature = "dim2")]
+                {
+                    PrismaticJoint::new(anchor1, axis1, anchor2, axis).into()
+                }
+                #[cfg(feature = "dim3")]
+                {
+                    // Use the same tangent on both sides so the joint doesn't rotate the
+                    // child rigid-body to align their orthonormal bases.
+                    let tangent = axis.orthonormal_basis()[0];
+                    PrismaticJoint::new(
+                        anchor1,
+                        axis1,
+                        relative.rotation * tangent,
+                        anchor2,
+                        axis,
+                        tangent,
+                    )
+                    .into()
+                }
+            }
+            #[cfg(feature = "dim3")]
+            ParentJointKind::Revolute(axis) => {
+                RevoluteJoint::new(anchor1, relative.rotation * axis, anchor2, axis).into()
+            }
+        }
+    }
+}
+
+/// System responsible for creating the joints of the `JointToParent` components.
+pub fn create_parent_joints_system(
+    mut commands: Commands,
+    children: Query<(Entity, &JointToParent, &RigidBodyPosition)>,
+    bodies: Query<&RigidBodyPosition>,
+    parents: Query<&Parent>,
+) {
+    for (entity, joint, rb_pos) in children.iter() {
+        // Find the closest ancestor with a rigid-body.
+        let mut ancestor = entity;
+        let parent_body = loop {
+            match parents.get(ancestor) {
+                Ok(&Parent(parent)) => ancestor = parent,
+                Err(_) => break None,
+            }
+
+            if let Ok(parent_pos) = bodies.get(ancestor) {
+                break Some((ancestor, parent_pos));
+            }
+        };
+
+        // Wait for the hierarchy to be set up.
+        let (parent_entity, parent_pos) = match parent_body {
+            Some(parent_body) => parent_body,
+            None => continue,
+        };
+
+        let relative = parent_pos.position.inv_mul(&rb_pos.position);
+        commands
+            .entity(entity)
+            .remove::<JointToParent>()
+            .insert(JointBuilderComponent::new(
+                joint.params(&relative),
+                parent_entity,
+                entity,
+            ));
+    }
+}
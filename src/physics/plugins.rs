@@ -1,6 +1,7 @@
 use crate::physics;
 use crate::physics::{
-    JointsEntityMap, ModificationTracker, PhysicsHooksWithQueryObject, RapierConfiguration,
+    JointContactFilter, JointCreationError, JointsEntityMap, ModificationTracker,
+    PhysicsHooksWithQueryObject, RagdollCreationError, RapierConfiguration, RopeCreationError,
     SimulationToRenderTime,
 };
 use crate::prelude::IntersectionEvent;
@@ -9,6 +10,7 @@ use crate::rapier::pipeline::QueryPipeline;
 use bevy::app::Events;
 use bevy::ecs::query::WorldQuery;
 use bevy::prelude::*;
+use bevy::transform::TransformSystem;
 use rapier::dynamics::{CCDSolver, IntegrationParameters, IslandManager, JointSet};
 use rapier::geometry::{BroadPhase, NarrowPhase};
 use rapier::pipeline::PhysicsPipeline;
@@ -69,8 +71,12 @@ impl<UserData: 'static + WorldQuery + Send + Sync> Plugin for RapierPhysicsPlugi
         .insert_resource(CCDSolver::new())
         .insert_resource(Events::<IntersectionEvent>::default())
         .insert_resource(Events::<ContactEvent>::default())
+        .add_event::<JointCreationError>()
+        .add_event::<RagdollCreationError>()
+        .add_event::<RopeCreationError>()
         .insert_resource(SimulationToRenderTime::default())
         .insert_resource(JointsEntityMap::default())
+        .insert_resource(JointContactFilter::default())
         .insert_resource(ModificationTracker::default())
         .add_system_to_stage(
             PhysicsStages::FinalizeCreations,
@@ -78,6 +84,13 @@ impl<UserData: 'static + WorldQuery + Send + Sync> Plugin for RapierPhysicsPlugi
                 .system()
                 .label(physics::PhysicsSystems::AttachBodiesAndColliders),
         )
+        .add_system_to_stage(
+            PhysicsStages::FinalizeCreations,
+            physics::create_parent_joints_system
+                .system()
+                .label(physics::PhysicsSystems::CreateParentJoints)
+                .before(physics::PhysicsSystems::CreateJoints),
+        )
         .add_system_to_stage(
             PhysicsStages::FinalizeCreations,
             physics::create_joints_system
@@ -90,12 +103,68 @@ impl<UserData: 'static + WorldQuery + Send + Sync> Plugin for RapierPhysicsPlugi
                 .system()
                 .label(physics::PhysicsSystems::FinalizeColliderAttachToBodies),
         )
+        .add_system_to_stage(
+            CoreStage::Update,
+            physics::update_character_controllers_system
+                .system()
+                .label(physics::PhysicsSystems::UpdateCharacterControllers)
+                .before(physics::PhysicsSystems::StepWorld),
+        )
+        .add_system_to_stage(
+            CoreStage::Update,
+            physics::update_moving_platforms_system
+                .system()
+                .label(physics::PhysicsSystems::UpdateMovingPlatforms)
+                .after(physics::PhysicsSystems::UpdateCharacterControllers)
+                .before(physics::PhysicsSystems::StepWorld),
+        )
+        .add_system_to_stage(
+            CoreStage::Update,
+            physics::create_ragdolls_system
+                .system()
+                .label(physics::PhysicsSystems::CreateRagdolls),
+        )
+        .add_system_to_stage(
+            CoreStage::Update,
+            physics::create_ropes_system
+                .system()
+                .label(physics::PhysicsSystems::CreateRopes),
+        )
+        .add_system_to_stage(
+            CoreStage::Update,
+            physics::sync_joint_params_system
+                .system()
+                .label(physics::PhysicsSystems::SyncJointParams)
+                .before(physics::PhysicsSystems::StepWorld),
+        )
+        .add_system_to_stage(
+            CoreStage::Update,
+            physics::sync_joint_motors_system
+                .system()
+                .label(physics::PhysicsSystems::SyncJointMotors)
+                .after(physics::PhysicsSystems::SyncJointParams)
+                .before(physics::PhysicsSystems::StepWorld),
+        )
+        .add_system_to_stage(
+            CoreStage::Update,
+            physics::sync_joint_contacts_system
+                .system()
+                .label(physics::PhysicsSystems::SyncJointContacts)
+                .before(physics::PhysicsSystems::StepWorld),
+        )
         .add_system_to_stage(
             CoreStage::Update,
             physics::step_world_system::<UserData>
                 .system()
                 .label(physics::PhysicsSystems::StepWorld),
         )
+        .add_system_to_stage(
+            CoreStage::Update,
+            physics::update_ragdolls_system
+                .system()
+                .label(physics::PhysicsSystems::UpdateRagdolls)
+                .after(physics::PhysicsSystems::StepWorld),
+        )
         .add_system_to_stage(
             PhysicsStages::SyncTransforms,
             physics::sync_transforms
@@ -107,6 +176,30 @@ impl<UserData: 'static + WorldQuery + Send + Sync> Plugin for RapierPhysicsPlugi
             physics::collect_removals
                 .system()
                 .label(physics::PhysicsSystems::CollectRemovals),
+        )
+        .add_system_to_stage(
+            CoreStage::PostUpdate,
+            physics::update_cast_sensors_system
+                .system()
+                .label(physics::PhysicsSystems::UpdateCastSensors)
+                .after(TransformSystem::TransformPropagate),
+        );
+        #[cfg(feature = "dim3")]
+        app.add_event::<physics::AsyncColliderError>();
+        #[cfg(feature = "dim3")]
+        app.add_system_to_stage(
+            PhysicsStages::FinalizeCreations,
+            physics::create_async_colliders_system
+                .system()
+                .label(physics::PhysicsSystems::CreateAsyncColliders)
+                .before(physics::PhysicsSystems::AttachBodiesAndColliders),
+        );
+        #[cfg(feature = "dim3")]
+        app.add_system_to_stage(
+            CoreStage::Update,
+            physics::create_scene_colliders_system
+                .system()
+                .label(physics::PhysicsSystems::CreateSceneColliders),
         );
         if app
             .world()
@@ -117,3 +210,19 @@ impl<UserData: 'static + WorldQuery + Send + Sync> Plugin for RapierPhysicsPlugi
         }
     }
 }
+
+#[cfg(test)]
+/// A headless app running one physics step of `IntegrationParameters::dt` at each update.
+pub(crate) fn test_app() -> App {
+    let mut app = App::build();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(bevy::transform::TransformPlugin)
+        .add_plugin(RapierPhysicsPlugin::<NoUserData>::default());
+
+    let mut app = app.app;
+    app.world
+        .get_resource_mut::<RapierConfiguration>()
+        .unwrap()
+        .timestep_mode = physics::TimestepMode::FixedTimestep;
+    app
+}
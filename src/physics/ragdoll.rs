@@ -0,0 +1,443 @@
+use crate::physics::{
+    physics_isometry, sync_transform, ColliderBundle, JointBuilderComponent, RapierConfiguration,
+    RigidBodyBundle,
+};
+use crate::rapier::dynamics::{BallJoint, RigidBodyPosition, RigidBodyType};
+use crate::rapier::geometry::{ColliderFlags, ColliderMassProps, ColliderShape, InteractionGroups};
+use crate::rapier::math::{Isometry, Point, Real};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// The description of a bone simulated by a ragdoll.
+///
+/// The bones are connected by Rapier ball joints, which don't support angular limits in this
+/// version of Rapier. Instead, the motor of the joint, configured with `RagdollBone::motor`,
+/// pulls the bone back toward its initial orientation relative to its parent bone, but doesn't
+/// prevent it from reaching any orientation.
+#[derive(Clone)]
+pub struct RagdollBone {
+    /// The shape of the collider attached to the bone.
+    pub shape: ColliderShape,
+    /// The position of the collider relative to the bone, in physics units.
+    pub position: Isometry<Real>,
+    /// The density of the collider.
+    pub density: Real,
+    /// The collision groups of the collider.
+    pub collision_groups: InteractionGroups,
+    /// Whether the collider of this bone can collide with the collider of its parent bone.
+    ///
+    /// Defaults to `false`, since the colliders of two connected bones typically overlap.
+    pub contacts_with_parent: bool,
+    /// The stiffness of the motor pulling the bone toward its initial orientation relative to
+    /// its parent bone.
+    pub stiffness: Real,
+    /// The damping of the motor pulling the bone toward its initial orientation relative to
+    /// its parent bone.
+    pub damping: Real,
+}
+
+impl RagdollBone {
+    /// A bone with the given collider shape, centered on the bone's origin.
+    pub fn new(shape: ColliderShape) -> Self {
+        Self {
+            shape,
+            position: Isometry::identity(),
+            density: 1.0,
+            collision_groups: InteractionGroups::all(),
+            contacts_with_parent: false,
+            stiffness: 0.0,
+            damping: 0.0,
+        }
+    }
+
+    /// Sets the position of the collider relative to the bone.
+    pub fn position(mut self, position: Isometry<Real>) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Sets the density of the collider.
+    pub fn density(mut self, density: Real) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Sets the collision groups of the collider.
+    pub fn collision_groups(mut self, groups: InteractionGroups) -> Self {
+        self.collision_groups = groups;
+        self
+    }
+
+    /// Sets whether the collider of this bone can collide with the collider of its parent bone.
+    pub fn contacts_with_parent(mut self, enabled: bool) -> Self {
+        self.contacts_with_parent = enabled;
+        self
+    }
+
+    /// Sets the stiffness and damping of the motor pulling the bone toward its initial
+    /// orientation relative to its parent bone.
+    pub fn motor(mut self, stiffness: Real, damping: Real) -> Self {
+        self.stiffness = stiffness;
+        self.damping = damping;
+        self
+    }
+}
+
+/// A component building a ragdoll from the entity hierarchy below its entity.
+///
+/// The bones are identified by their `Name` component, which makes this usable with the
+/// hierarchies spawned from glTF scenes. The ragdoll is built once all the bones are found
+/// below the entity of this component. A rigid-body with the bone's collider is then
+/// spawned for each bone, and each bone is connected by a ball joint to the closest of its
+/// ancestors that is also a bone of the ragdoll. The builder is finally replaced by a
+/// `Ragdoll` component.
+///
+/// The builder is abandoned, and a `RagdollCreationError` event is sent, if some bones are
+/// still missing after its timeout, which is 600 frames by default.
+#[derive(Clone)]
+pub struct RagdollBuilder {
+    bones: Vec<(String, RagdollBone)>,
+    simulated: bool,
+    timeout: Option<u32>,
+    pending_frames: u32,
+}
+
+impl Default for RagdollBuilder {
+    fn default() -> Self {
+        Self {
+            bones: vec![],
+            simulated: false,
+            timeout: Some(600),
+            pending_frames: 0,
+        }
+    }
+}
+
+impl RagdollBuilder {
+    /// A ragdoll builder without any bone.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the bone with the given name to the ragdoll.
+    pub fn bone(mut self, name: impl Into<String>, bone: RagdollBone) -> Self {
+        self.bones.push((name.into(), bone));
+        self
+    }
+
+    /// Sets whether the ragdoll starts simulated, or driven by the bones' transforms.
+    pub fn simulated(mut self, simulated: bool) -> Self {
+        self.simulated = simulated;
+        self
+    }
+
+    /// Sets the number of frames this builder waits for its bones before being abandoned. If
+    /// `None`, it waits as long as its entity exists.
+    pub fn timeout(mut self, frames: Option<u32>) -> Self {
+        self.timeout = frames;
+        self
+    }
+
+    /// The number of frames this builder has been waiting for its bones.
+    pub fn pending_frames(&self) -> u32 {
+        self.pending_frames
+    }
+}
+
+/// An event sent when a `RagdollBuilder` is abandoned because some of its bones were not found
+/// before its timeout.
+///
+/// The `RagdollBuilder` is removed from its entity when this event is sent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RagdollCreationError {
+    /// The entity with the `RagdollBuilder`.
+    pub ragdoll: Entity,
+    /// The names of the bones not found below the entity.
+    pub missing_bones: Vec<String>,
+}
+
+/// A component controlling a ragdoll built by a `RagdollBuilder`.
+#[derive(Clone, Debug)]
+pub struct Ragdoll {
+    /// If `true`, the ragdoll's bodies are dynamic and their positions are written into the
+    /// bones' `Transform`s. Otherwise, the ragdoll's bodies are kinematic and follow the
+    /// bones' `GlobalTransform`s, which are typically driven by an animation.
+    ///
+    /// The velocities of the bodies are preserved when switching from an animation to the
+    /// simulation.
+    pub simulated: bool,
+    bodies: Vec<Entity>,
+}
+
+impl Ragdoll {
+    /// The entities of the rigid-bodies of the ragdoll, each parent bone before its children.
+    pub fn bodies(&self) -> &[Entity] {
+        &self.bodies
+    }
+}
+
+/// A component attached to the rigid-bodies of a ragdoll.
+#[derive(Copy, Clone, Debug)]
+pub struct RagdollBody {
+    /// The entity with the `Ragdoll` component.
+    pub ragdoll: Entity,
+    /// The entity of the bone simulated by this rigid-body.
+    pub bone: Entity,
+    /// The rigid-body of the parent bone, if any.
+    pub parent: Option<Entity>,
+}
+
+fn world_transform(
+    entity: Entity,
+    hierarchy: &Query<(Option<&Name>, &Transform, Option<&Children>)>,
+    parents: &Query<&Parent>,
+) -> GlobalTransform {
+    let local = hierarchy
+        .get(entity)
+        .map(|(_, transform, _)| *transform)
+        .unwrap_or_default();
+
+    match parents.get(entity) {
+        Ok(parent) => world_transform(parent.0, hierarchy, parents).mul_transform(local),
+        Err(_) => GlobalTransform::from(local),
+    }
+}
+
+/// System responsible for creating the rigid-bodies and joints of the `RagdollBuilder`s.
+pub fn create_ragdolls_system(
+    mut commands: Commands,
+    configuration: Res<RapierConfiguration>,
+    mut builders: Query<(Entity, &mut RagdollBuilder)>,
+    hierarchy: Query<(Option<&Name>, &Transform, Option<&Children>)>,
+    parents: Query<&Parent>,
+    mut errors: EventWriter<RagdollCreationError>,
+) {
+    for (root, mut builder) in builders.iter_mut() {
+        // The bones, each parent before its children, with the index of their parent bone.
+        let mut bones = vec![];
+        let root_world = world_transform(root, &hierarchy, &parents);
+        let mut stack = vec![(root, root_world, None)];
+
+        while let Some((entity, world, parent_bone)) = stack.pop() {
+            let (name, children) = match hierarchy.get(entity) {
+                Ok((name, _, children)) => (name, children),
+                Err(_) => continue,
+            };
+
+            let mut parent_bone = parent_bone;
+            let desc = name.and_then(|name| {
+                builder
+                    .bones
+                    .iter()
+                    .find(|(bone_name, _)| bone_name.as_str() == name.as_str())
+            });
+
+            if let Some(desc) = desc {
+                bones.push((entity, world, parent_bone, desc));
+                parent_bone = Some(bones.len() - 1);
+            }
+
+            for child in children.into_iter().flat_map(|children| children.iter()) {
+                if let Ok((_, transform, _)) = hierarchy.get(*child) {
+                    stack.push((*child, world.mul_transform(*transform), parent_bone));
+                }
+            }
+        }
+
+        // Wait for the hierarchy to be fully spawned.
+        if bones.len() != builder.bones.len() {
+            if builder
+                .timeout
+                .map(|timeout| builder.pending_frames >= timeout)
+                == Some(true)
+            {
+                let missing_bones = builder
+                    .bones
+                    .iter()
+                    .map(|(name, _)| name)
+                    .filter(|name| !bones.iter().any(|(.., (found, _))| found == *name))
+                    .cloned()
+                    .collect();
+
+                errors.send(RagdollCreationError {
+                    ragdoll: root,
+                    missing_bones,
+                });
+                commands.entity(root).remove::<RagdollBuilder>();
+            } else {
+                builder.pending_frames += 1;
+            }
+
+            continue;
+        }
+
+        let body_type = if builder.simulated {
+            RigidBodyType::Dynamic
+        } else {
+            RigidBodyType::KinematicPositionBased
+        };
+        let mut bodies: Vec<(Entity, Isometry<Real>)> = vec![];
+
+        for (bone, world, parent_bone, (_, desc)) in bones {
+            let position = physics_isometry(&world, configuration.scale);
+            let parent = parent_bone.map(|i| bodies[i]);
+            let body = commands
+                .spawn_bundle(RigidBodyBundle {
+                    body_type,
+                    position: position.into(),
+                    ..Default::default()
+                })
+                .insert_bundle(ColliderBundle {
+                    shape: desc.shape.clone(),
+                    position: desc.position.into(),
+                    flags: ColliderFlags {
+                        collision_groups: desc.collision_groups,
+                        ..Default::default()
+                    },
+                    mass_properties: ColliderMassProps::Density(desc.density),
+                    ..Default::default()
+                })
+                .insert(RagdollBody {
+                    ragdoll: root,
+                    bone,
+                    parent: parent.map(|(parent_body, _)| parent_body),
+                })
+                .id();
+
+            if let Some((parent_body, parent_position)) = parent {
+                let anchor = Point::from(position.translation.vector);
+                let mut joint = BallJoint::new(
+                    parent_position.inverse_transform_point(&anchor),
+                    Point::origin(),
+                );
+
+                if desc.stiffness != 0.0 || desc.damping != 0.0 {
+                    joint.configure_motor_position(
+                        parent_position.rotation.inverse() * position.rotation,
+                        desc.stiffness,
+                        desc.damping,
+                    );
+                }
+
+                commands.spawn_bundle((JointBuilderComponent::new(joint, parent_body, body)
+                    .contacts_enabled(desc.contacts_with_parent),));
+            }
+
+            bodies.push((body, position));
+        }
+
+        commands
+            .entity(root)
+            .remove::<RagdollBuilder>()
+            .insert(Ragdoll {
+                simulated: builder.simulated,
+                bodies: bodies.into_iter().map(|(body, _)| body).collect(),
+            });
+    }
+}
+
+/// The world transform of the parent of a bone, taking into account the bones already moved
+/// by the simulation.
+fn parent_world_transform(
+    bone: Entity,
+    bone_worlds: &HashMap<Entity, GlobalTransform>,
+    parents: &Query<&Parent>,
+    global_transforms: &Query<&GlobalTransform>,
+    transforms: &mut Query<&mut Transform>,
+) -> GlobalTransform {
+    let parent = match parents.get(bone) {
+        Ok(parent) => parent.0,
+        Err(_) => return GlobalTransform::identity(),
+    };
+
+    // Walk up to the closest bone moved by the simulation, if any.
+    let mut locals = vec![];
+    let mut ancestor = parent;
+    loop {
+        if let Some(world) = bone_worlds.get(&ancestor) {
+            return locals
+                .into_iter()
+                .rev()
+                .fold(*world, |world, local| world.mul_transform(local));
+        }
+
+        locals.push(
+            transforms
+                .get_mut(ancestor)
+                .map(|transform| *transform)
+                .unwrap_or_default(),
+        );
+
+        match parents.get(ancestor) {
+            Ok(next) => ancestor = next.0,
+            Err(_) => break,
+        }
+    }
+
+    global_transforms.get(parent).copied().unwrap_or_default()
+}
+
+/// System responsible for moving the rigid-bodies of the kinematic `Ragdoll`s with their
+/// bones, and the bones of the simulated `Ragdoll`s with their rigid-bodies.
+pub fn update_ragdolls_system(
+    configuration: Res<RapierConfiguration>,
+    ragdolls: Query<&Ragdoll>,
+    mut bodies: Query<(&RagdollBody, &mut RigidBodyType, &mut RigidBodyPosition)>,
+    parents: Query<&Parent>,
+    global_transforms: Query<&GlobalTransform>,
+    mut transforms: Query<&mut Transform>,
+) {
+    for ragdoll in ragdolls.iter() {
+        let body_type = if ragdoll.simulated {
+            RigidBodyType::Dynamic
+        } else {
+            RigidBodyType::KinematicPositionBased
+        };
+        // The world transforms of the bones already moved by the simulation.
+        let mut bone_worlds: HashMap<Entity, GlobalTransform> = HashMap::new();
+
+        for body in &ragdoll.bodies {
+            let (ragdoll_body, mut rb_type, mut rb_pos) = match bodies.get_mut(*body) {
+                Ok(body) => body,
+                Err(_) => continue,
+            };
+
+            if *rb_type != body_type {
+                *rb_type = body_type;
+            }
+
+            let bone_global = match global_transforms.get(ragdoll_body.bone) {
+                Ok(bone_global) => *bone_global,
+                Err(_) => continue,
+            };
+
+            if !ragdoll.simulated {
+                let position = physics_isometry(&bone_global, configuration.scale);
+                if rb_pos.next_position != position {
+                    rb_pos.next_position = position;
+                }
+                continue;
+            }
+
+            let mut world = Transform::from(bone_global);
+            sync_transform(&rb_pos.position, configuration.scale, &mut world);
+            bone_worlds.insert(ragdoll_body.bone, GlobalTransform::from(world));
+
+            let parent_world = parent_world_transform(
+                ragdoll_body.bone,
+                &bone_worlds,
+                &parents,
+                &global_transforms,
+                &mut transforms,
+            );
+
+            if let Ok(mut transform) = transforms.get_mut(ragdoll_body.bone) {
+                let inv_rotation = parent_world.rotation.conjugate();
+                transform.translation = inv_rotation
+                    * (world.translation - parent_world.translation)
+                    / parent_world.scale;
+                transform.rotation = inv_rotation * world.rotation;
+            }
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use crate::physics::{
     ColliderBundle, ColliderComponentsQuery, ColliderComponentsSet, IntoEntity, IntoHandle,
-    JointHandleComponent, RigidBodyComponentsQuery, RigidBodyComponentsSet,
+    JointHandleComponent, Joints, RigidBodyComponentsQuery, RigidBodyComponentsSet,
 };
 use crate::rapier::prelude::*;
 use bevy::ecs::query::WorldQuery;
 use bevy::prelude::*;
-use rapier::data::{ComponentSet, ComponentSetMut};
-use std::collections::HashMap;
+use rapier::data::{ComponentSet, ComponentSetMut, ComponentSetOption};
+use std::collections::{HashMap, HashSet};
 use std::sync::RwLock;
 
 /// The different ways of adjusting the timestep length.
@@ -40,6 +40,10 @@ pub struct RapierConfiguration {
     pub query_pipeline_active: bool,
     /// Specifies the way the timestep length should be adjusted at each frame.
     pub timestep_mode: TimestepMode,
+    /// Specifies the number of frames a `JointBuilderComponent`, or a `RopeBuilder`, waits for
+    /// the rigid-bodies of its entities before being abandoned. If `None`, it waits as long as
+    /// its entities exist.
+    pub joint_creation_timeout: Option<u32>,
 }
 
 impl Default for RapierConfiguration {
@@ -50,6 +54,7 @@ impl Default for RapierConfiguration {
             physics_pipeline_active: true,
             query_pipeline_active: true,
             timestep_mode: TimestepMode::VariableTimestep,
+            joint_creation_timeout: None,
         }
     }
 }
@@ -89,7 +94,61 @@ pub struct SimulationToRenderTime {
 
 /// HashMaps of Bevy Entity to Rapier handles
 #[derive(Default)]
-pub struct JointsEntityMap(pub(crate) HashMap<Entity, JointHandle>);
+pub struct JointsEntityMap {
+    /// The joints of the entities with a `JointHandleComponent`.
+    pub(crate) joints: HashMap<Entity, JointHandle>,
+    /// The joints of the entities with a `Joints` component.
+    pub(crate) joint_lists: HashMap<Entity, Vec<JointHandle>>,
+}
+
+/// The pairs of rigid-bodies attached by joints created with their contacts disabled.
+///
+/// See `JointBuilderComponent::contacts_enabled`, including the restriction on toggling
+/// `ActiveHooks::FILTER_CONTACT_PAIRS` on the colliders of these rigid-bodies.
+#[derive(Default)]
+pub struct JointContactFilter {
+    /// The rigid-bodies attached by each joint with its contacts disabled.
+    pub(crate) joints: HashMap<JointHandle, (RigidBodyHandle, RigidBodyHandle)>,
+    /// The pairs of rigid-bodies that can't collide, ordered by index.
+    pub(crate) pairs: HashSet<(RigidBodyHandle, RigidBodyHandle)>,
+    /// The colliders on which the plugin enabled `ActiveHooks::FILTER_CONTACT_PAIRS`.
+    pub(crate) hooked_colliders: HashSet<ColliderHandle>,
+}
+
+impl JointContactFilter {
+    fn pair(body1: RigidBodyHandle, body2: RigidBodyHandle) -> (RigidBodyHandle, RigidBodyHandle) {
+        if body1.into_raw_parts() <= body2.into_raw_parts() {
+            (body1, body2)
+        } else {
+            (body2, body1)
+        }
+    }
+
+    /// Disables the contacts between the rigid-bodies attached by the given joint.
+    pub(crate) fn disable(
+        &mut self,
+        joint: JointHandle,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+    ) {
+        let pair = Self::pair(body1, body2);
+        self.joints.insert(joint, pair);
+        self.pairs.insert(pair);
+    }
+
+    /// Forgets the joints removed from the given `JointSet`.
+    pub(crate) fn retain_joints(&mut self, joints: &JointSet) {
+        if self.joints.keys().any(|handle| !joints.contains(*handle)) {
+            self.joints.retain(|handle, _| joints.contains(*handle));
+            self.pairs = self.joints.values().copied().collect();
+        }
+    }
+
+    /// Can the colliders of these two rigid-bodies collide, as far as their joints are concerned?
+    pub fn contacts_enabled(&self, body1: RigidBodyHandle, body2: RigidBodyHandle) -> bool {
+        !self.pairs.contains(&Self::pair(body1, body2))
+    }
+}
 
 pub struct ModificationTracker {
     pub(crate) modified_bodies: Vec<RigidBodyHandle>,
@@ -99,6 +158,7 @@ pub struct ModificationTracker {
     // NOTE: right now, this actually contains an Entity instead of the JointHandle.
     //       but we will switch to JointHandle soon.
     pub(crate) removed_joints: Vec<JointHandle>,
+    pub(crate) removed_joint_lists: Vec<Entity>,
     // We need to maintain these two because we have to access them
     // when an entity containing a collider/rigid-body has been despawn.
     pub(crate) body_colliders: HashMap<RigidBodyHandle, Vec<ColliderHandle>>,
@@ -113,6 +173,7 @@ impl Default for ModificationTracker {
             removed_bodies: vec![],
             removed_colliders: vec![],
             removed_joints: vec![],
+            removed_joint_lists: vec![],
             body_colliders: HashMap::new(),
             colliders_parent: HashMap::new(),
         }
@@ -126,6 +187,7 @@ impl ModificationTracker {
         self.removed_bodies.clear();
         self.removed_colliders.clear();
         self.removed_joints.clear();
+        self.removed_joint_lists.clear();
     }
 
     pub fn detect_modifications(
@@ -202,6 +264,7 @@ impl ModificationTracker {
         removed_bodies: RemovedComponents<RigidBodyChanges>,
         removed_colliders: RemovedComponents<ColliderChanges>,
         removed_joints: RemovedComponents<JointHandleComponent>,
+        removed_joint_lists: RemovedComponents<Joints>,
     ) {
         self.removed_bodies.extend(
             removed_bodies
@@ -218,6 +281,7 @@ impl ModificationTracker {
                 .iter()
                 .map(|e| IntoHandle::<JointHandle>::handle(e)),
         );
+        self.removed_joint_lists.extend(removed_joint_lists.iter());
     }
 
     pub fn propagate_removals<Bodies>(
@@ -245,9 +309,17 @@ impl ModificationTracker {
                 }
             }
 
-            let mut removed_joints =
+            // These joints are already removed from the `JointSet`, so only forget them.
+            let removed_joints =
                 joints.remove_joints_attached_to_rigid_body(*removed_body, islands, bodies);
-            self.removed_joints.append(&mut removed_joints);
+            if !removed_joints.is_empty() {
+                joints_map
+                    .joints
+                    .retain(|_, handle| !removed_joints.contains(handle));
+                for handles in joints_map.joint_lists.values_mut() {
+                    handles.retain(|handle| !removed_joints.contains(handle));
+                }
+            }
         }
 
         for removed_collider in self.removed_colliders.iter() {
@@ -278,11 +350,19 @@ impl ModificationTracker {
         }
 
         for removed_joints in self.removed_joints.iter() {
-            let joint_handle = joints_map.0.remove(&removed_joints.entity());
+            let joint_handle = joints_map.joints.remove(&removed_joints.entity());
             if let Some(joint_handle) = joint_handle {
                 joints.remove(joint_handle, islands, bodies, true);
             }
         }
+
+        for removed_joint_list in self.removed_joint_lists.iter() {
+            if let Some(joint_handles) = joints_map.joint_lists.remove(removed_joint_list) {
+                for joint_handle in joint_handles {
+                    joints.remove(joint_handle, islands, bodies, true);
+                }
+            }
+        }
     }
 }
 
@@ -344,6 +424,7 @@ pub struct PhysicsHooksWithQueryObject<UserData: WorldQuery>(
 pub(crate) struct PhysicsHooksWithQueryInstance<'a, 'b, UserData: WorldQuery> {
     pub user_data: Query<'a, UserData>,
     pub hooks: &'b dyn PhysicsHooksWithQuery<UserData>,
+    pub joint_contacts: &'b JointContactFilter,
 }
 
 impl<'aa, 'bb, 'a, 'b, 'c, 'd, 'e, 'f, UserData: WorldQuery>
@@ -354,7 +435,29 @@ impl<'aa, 'bb, 'a, 'b, 'c, 'd, 'e, 'f, UserData: WorldQuery>
         &self,
         context: &PairFilterContext<RigidBodyComponentsSet, ColliderComponentsSet>,
     ) -> Option<SolverFlags> {
-        self.hooks.filter_contact_pair(context, &self.user_data)
+        if let (Some(body1), Some(body2)) = (context.rigid_body1, context.rigid_body2) {
+            if !self.joint_contacts.contacts_enabled(body1, body2) {
+                return None;
+            }
+        }
+
+        // Only call the user's hooks if they were enabled by the user on one of the colliders.
+        let user_hooks = [context.collider1, context.collider2]
+            .iter()
+            .any(|collider| {
+                let co_flags: Option<&ColliderFlags> = context.colliders.get(collider.0);
+                let active_hooks = co_flags
+                    .map(|co_flags| co_flags.active_hooks)
+                    .unwrap_or_else(ActiveHooks::empty);
+                !self.joint_contacts.hooked_colliders.contains(collider)
+                    && active_hooks.contains(ActiveHooks::FILTER_CONTACT_PAIRS)
+            });
+
+        if user_hooks {
+            self.hooks.filter_contact_pair(context, &self.user_data)
+        } else {
+            Some(SolverFlags::default())
+        }
     }
 
     fn filter_intersection_pair(
@@ -0,0 +1,548 @@
+use crate::physics::{
+    ColliderBundle, JointBuilderComponent, JointCreationErrorKind, RapierConfiguration,
+    RigidBodyBundle,
+};
+#[cfg(feature = "dim3")]
+use crate::rapier::dynamics::RevoluteJoint;
+use crate::rapier::dynamics::{BallJoint, JointParams, RigidBodyPosition, RigidBodyType};
+use crate::rapier::geometry::{ColliderFlags, ColliderMassProps, ColliderShape, InteractionGroups};
+use crate::rapier::math::{Isometry, Point, Real, Rotation, Vector};
+use bevy::ecs::entity::Entities;
+use bevy::prelude::*;
+#[cfg(feature = "dim3")]
+use na::Unit;
+
+/// Where an end of a rope is attached.
+#[derive(Copy, Clone, Debug)]
+pub enum RopeAnchor {
+    /// The end of the rope is pinned at this world-space point, in physics units.
+    ///
+    /// A static rigid-body is spawned at this point to attach the rope to.
+    Point(Point<Real>),
+    /// The end of the rope is attached to the given point, expressed in the local frame of the
+    /// rigid-body of the given entity.
+    Body(Entity, Point<Real>),
+    /// The end of the rope is located at this world-space point, in physics units, but is not
+    /// attached to anything.
+    Free(Point<Real>),
+}
+
+/// One of the two ends of a rope.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RopeEnd {
+    /// The end of the first link of the rope.
+    Start,
+    /// The end of the last link of the rope.
+    End,
+}
+
+/// The kind of joints connecting the links of a rope.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RopeJoint {
+    /// Ball joints, letting the links rotate freely relative to each other.
+    Ball,
+    /// Revolute joints around the given axis, expressed in the local frame of the links, and of
+    /// the rigid-bodies the rope is attached to. This gives a chain bending in a single plane.
+    #[cfg(feature = "dim3")]
+    Revolute(Unit<Vector<Real>>),
+}
+
+/// A component building a rope (or a chain) between two anchors.
+///
+/// The rope is made of `segments` links laid out on the straight line between the two anchors,
+/// each with a rigid-body and a collider with the given shape. The local `y` axis of the links
+/// is aligned with the rope, so their shape should be elongated along this axis, e.g., with
+/// `ColliderShape::capsule` or `ColliderShape::cuboid`. Consecutive links are connected by a
+/// joint at their common end. The rope is built once the rigid-bodies of its anchors exist, and
+/// the builder is then replaced by a `Rope` component.
+///
+/// Like a `JointBuilderComponent`, the builder is abandoned, and a `RopeCreationError` event is
+/// sent, if the entity of an anchor is despawned, or if its rigid-body is still missing after
+/// `RapierConfiguration::joint_creation_timeout` frames.
+#[derive(Clone)]
+pub struct RopeBuilder {
+    /// The anchor of the start of the rope.
+    pub start: RopeAnchor,
+    /// The anchor of the end of the rope.
+    pub end: RopeAnchor,
+    /// The number of links of the rope.
+    pub segments: usize,
+    /// The shape of the collider of each link.
+    pub shape: ColliderShape,
+    /// The mass of each link.
+    pub mass: Real,
+    /// The kind of joints connecting the links.
+    pub joint: RopeJoint,
+    /// The collision groups of the colliders of the links.
+    ///
+    /// The colliders of two consecutive links overlap, so they should be configured so they
+    /// don't collide with each other.
+    pub collision_groups: InteractionGroups,
+    pub(crate) pending_frames: u32,
+}
+
+impl RopeBuilder {
+    /// A rope between the given anchors, made of `segments` links with the given shape.
+    pub fn new(start: RopeAnchor, end: RopeAnchor, segments: usize, shape: ColliderShape) -> Self {
+        Self {
+            start,
+            end,
+            segments,
+            shape,
+            mass: 1.0,
+            joint: RopeJoint::Ball,
+            collision_groups: InteractionGroups::all(),
+            pending_frames: 0,
+        }
+    }
+
+    /// Sets the mass of each link.
+    pub fn mass(mut self, mass: Real) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Sets the kind of joints connecting the links.
+    pub fn joint(mut self, joint: RopeJoint) -> Self {
+        self.joint = joint;
+        self
+    }
+
+    /// Sets the collision groups of the colliders of the links.
+    pub fn collision_groups(mut self, groups: InteractionGroups) -> Self {
+        self.collision_groups = groups;
+        self
+    }
+
+    /// The number of frames this builder has been waiting for the rigid-bodies of its anchors.
+    pub fn pending_frames(&self) -> u32 {
+        self.pending_frames
+    }
+}
+
+/// An event sent when a `RopeBuilder` is abandoned.
+///
+/// The `RopeBuilder` is removed from its entity when this event is sent.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RopeCreationError {
+    /// The entity with the `RopeBuilder`.
+    pub rope: Entity,
+    /// The end of the rope whose anchor caused the error.
+    pub end: RopeEnd,
+    /// The entity of the anchor that caused the error.
+    pub entity: Entity,
+    /// The reason of the error.
+    pub kind: JointCreationErrorKind,
+}
+
+/// The joint attaching an end of a rope to a rigid-body.
+#[derive(Copy, Clone, Debug)]
+struct RopeAttachment {
+    joint: Entity,
+    /// The static rigid-body spawned for a `RopeAnchor::Point`.
+    pin: Option<Entity>,
+}
+
+/// A component describing a rope built by a `RopeBuilder`.
+///
+/// Its methods modify the rope through `Commands`, so their effects are visible after the
+/// commands are applied.
+#[derive(Clone, Debug)]
+pub struct Rope {
+    links: Vec<Entity>,
+    joints: Vec<Entity>,
+    start: Option<RopeAttachment>,
+    end: Option<RopeAttachment>,
+    half_length: Real,
+    joint: RopeJoint,
+}
+
+impl Rope {
+    /// The entities of the rigid-bodies of the links, from the start to the end of the rope.
+    pub fn links(&self) -> &[Entity] {
+        &self.links
+    }
+
+    /// The entities of the joints connecting the links. The `i`-th joint connects the `i`-th
+    /// and the `i + 1`-th links.
+    pub fn joints(&self) -> &[Entity] {
+        &self.joints
+    }
+
+    /// The entity of the joint attaching the given end of the rope, if it is attached.
+    pub fn attachment(&self, end: RopeEnd) -> Option<Entity> {
+        match end {
+            RopeEnd::Start => self.start,
+            RopeEnd::End => self.end,
+        }
+        .map(|attachment| attachment.joint)
+    }
+
+    /// The entity of the link at the given end of the rope.
+    pub fn end_link(&self, end: RopeEnd) -> Option<Entity> {
+        match end {
+            RopeEnd::Start => self.links.first().copied(),
+            RopeEnd::End => self.links.last().copied(),
+        }
+    }
+
+    /// Attaches the given end of the rope to a point expressed in the local frame of the
+    /// rigid-body of `body`, replacing the previous attachment of this end.
+    pub fn attach(
+        &mut self,
+        commands: &mut Commands,
+        end: RopeEnd,
+        body: Entity,
+        local_anchor: Point<Real>,
+    ) {
+        self.detach(commands, end);
+
+        if let Some(link) = self.end_link(end) {
+            let joint = self.attachment_joint(commands, end, link, body, local_anchor);
+            let attachment = Some(RopeAttachment { joint, pin: None });
+
+            match end {
+                RopeEnd::Start => self.start = attachment,
+                RopeEnd::End => self.end = attachment,
+            }
+        }
+    }
+
+    /// Detaches the given end of the rope, if it is attached.
+    ///
+    /// The static rigid-body spawned for a `RopeAnchor::Point` is despawned.
+    pub fn detach(&mut self, commands: &mut Commands, end: RopeEnd) {
+        let attachment = match end {
+            RopeEnd::Start => self.start.take(),
+            RopeEnd::End => self.end.take(),
+        };
+
+        if let Some(attachment) = attachment {
+            commands.entity(attachment.joint).despawn();
+
+            if let Some(pin) = attachment.pin {
+                commands.entity(pin).despawn();
+            }
+        }
+    }
+
+    /// Cuts the rope by removing the joint between the `segment`-th and the `segment + 1`-th
+    /// links.
+    ///
+    /// This rope keeps the links up to the `segment`-th one. The other links, with the
+    /// attachment of the end of the rope, are returned as a new rope which can be inserted on
+    /// another entity. Returns `None`, without cutting anything, if `segment` is the last link.
+    pub fn cut(&mut self, commands: &mut Commands, segment: usize) -> Option<Rope> {
+        if segment + 1 >= self.links.len() {
+            return None;
+        }
+
+        let links = self.links.split_off(segment + 1);
+        let joints = self.joints.split_off(segment + 1);
+        let cut_joint = self.joints.pop()?;
+        commands.entity(cut_joint).despawn();
+
+        Some(Rope {
+            links,
+            joints,
+            start: None,
+            end: self.end.take(),
+            half_length: self.half_length,
+            joint: self.joint,
+        })
+    }
+
+    /// The anchor of the given end of the rope, in the local frame of its link.
+    fn link_anchor(&self, end: RopeEnd) -> Point<Real> {
+        match end {
+            RopeEnd::Start => Point::origin() - Vector::y() * self.half_length,
+            RopeEnd::End => Point::origin() + Vector::y() * self.half_length,
+        }
+    }
+
+    fn joint_params(&self, anchor1: Point<Real>, anchor2: Point<Real>) -> JointParams {
+        match self.joint {
+            RopeJoint::Ball => BallJoint::new(anchor1, anchor2).into(),
+            #[cfg(feature = "dim3")]
+            RopeJoint::Revolute(axis) => RevoluteJoint::new(anchor1, axis, anchor2, axis).into(),
+        }
+    }
+
+    fn attachment_joint(
+        &self,
+        commands: &mut Commands,
+        end: RopeEnd,
+        link: Entity,
+        body: Entity,
+        local_anchor: Point<Real>,
+    ) -> Entity {
+        let params = self.joint_params(self.link_anchor(end), local_anchor);
+        commands
+            .spawn_bundle((JointBuilderComponent::new(params, link, body),))
+            .id()
+    }
+}
+
+/// The world-space position of an anchor, if its rigid-body exists.
+fn anchor_position(
+    anchor: &RopeAnchor,
+    positions: &Query<&RigidBodyPosition>,
+) -> Option<Point<Real>> {
+    match anchor {
+        RopeAnchor::Point(point) | RopeAnchor::Free(point) => Some(*point),
+        RopeAnchor::Body(entity, local_anchor) => positions
+            .get(*entity)
+            .ok()
+            .map(|rb_pos| rb_pos.position * local_anchor),
+    }
+}
+
+/// The rotation aligning the local `y` axis of the links with the given direction.
+fn link_rotation(dir: &Vector<Real>) -> Rotation<Real> {
+    #[cfg(feature = "dim2")]
+    {
+        Rotation::rotation_between(&Vector::y(), dir)
+    }
+    #[cfg(feature = "dim3")]
+    {
+        Rotation::rotation_between(&Vector::y(), dir)
+            .unwrap_or_else(|| Rotation::from_axis_angle(&Vector::x_axis(), std::f32::consts::PI))
+    }
+}
+
+/// The positions of the `segments` links laid out on the straight line from `start` to `end`,
+/// and the half-length of each link.
+fn link_layout(
+    start: &Point<Real>,
+    end: &Point<Real>,
+    segments: usize,
+) -> (Vec<Isometry<Real>>, Real) {
+    let segments = segments.max(1);
+    let delta = end - start;
+    let dir = delta.try_normalize(1.0e-6).unwrap_or_else(|| -Vector::y());
+    let half_length = delta.norm() / (2.0 * segments as Real);
+    let rotation = link_rotation(&dir);
+    let positions = (0..segments)
+        .map(|i| {
+            let center = start + dir * half_length * (2 * i + 1) as Real;
+            Isometry::from_parts(center.coords.into(), rotation)
+        })
+        .collect();
+
+    (positions, half_length)
+}
+
+/// The error of the anchor of the given end of a rope whose rigid-body doesn't exist.
+fn anchor_error(
+    end: RopeEnd,
+    anchor: &RopeAnchor,
+    entities: &Entities,
+    positions: &Query<&RigidBodyPosition>,
+) -> Option<(RopeEnd, Entity, JointCreationErrorKind)> {
+    match anchor {
+        RopeAnchor::Body(entity, _) if !entities.contains(*entity) => {
+            Some((end, *entity, JointCreationErrorKind::EntityNotFound))
+        }
+        RopeAnchor::Body(entity, _) if positions.get(*entity).is_err() => {
+            Some((end, *entity, JointCreationErrorKind::RigidBodyNotFound))
+        }
+        _ => None,
+    }
+}
+
+/// System responsible for creating the rigid-bodies and joints of the `RopeBuilder`s.
+pub fn create_ropes_system(
+    mut commands: Commands,
+    (configuration, entities): (Res<RapierConfiguration>, &Entities),
+    mut builders: Query<(Entity, &mut RopeBuilder)>,
+    positions: Query<&RigidBodyPosition>,
+    mut errors: EventWriter<RopeCreationError>,
+) {
+    for (entity, mut builder) in builders.iter_mut() {
+        // Report the despawned entities first since the rope can't be created in this case.
+        let error = [
+            anchor_error(RopeEnd::Start, &builder.start, entities, &positions),
+            anchor_error(RopeEnd::End, &builder.end, entities, &positions),
+        ]
+        .iter()
+        .flatten()
+        .copied()
+        .min_by_key(|(_, _, kind)| *kind != JointCreationErrorKind::EntityNotFound);
+
+        // Wait for the rigid-bodies of the anchors to be created.
+        if let Some((end, anchor, kind)) = error {
+            let timed_out = configuration
+                .joint_creation_timeout
+                .map(|timeout| builder.pending_frames >= timeout)
+                .unwrap_or(false);
+
+            if kind == JointCreationErrorKind::EntityNotFound || timed_out {
+                errors.send(RopeCreationError {
+                    rope: entity,
+                    end,
+                    entity: anchor,
+                    kind,
+                });
+                commands.entity(entity).remove::<RopeBuilder>();
+            } else {
+                builder.pending_frames += 1;
+            }
+
+            continue;
+        }
+
+        let (start, end) = match (
+            anchor_position(&builder.start, &positions),
+            anchor_position(&builder.end, &positions),
+        ) {
+            (Some(start), Some(end)) => (start, end),
+            _ => continue,
+        };
+
+        let (link_positions, half_length) = link_layout(&start, &end, builder.segments);
+        let rotation = link_positions[0].rotation;
+
+        // The density giving the requested mass to each link.
+        let inv_unit_mass = builder.shape.mass_properties(1.0).inv_mass;
+        let density = if inv_unit_mass > 0.0 {
+            builder.mass * inv_unit_mass
+        } else {
+            1.0
+        };
+
+        let links: Vec<Entity> = link_positions
+            .iter()
+            .map(|position| {
+                commands
+                    .spawn_bundle(RigidBodyBundle {
+                        position: (*position).into(),
+                        ..Default::default()
+                    })
+                    .insert_bundle(ColliderBundle {
+                        shape: builder.shape.clone(),
+                        flags: ColliderFlags {
+                            collision_groups: builder.collision_groups,
+                            ..Default::default()
+                        },
+                        mass_properties: ColliderMassProps::Density(density),
+                        ..Default::default()
+                    })
+                    .id()
+            })
+            .collect();
+
+        let mut rope = Rope {
+            links,
+            joints: vec![],
+            start: None,
+            end: None,
+            half_length,
+            joint: builder.joint,
+        };
+
+        let start_anchor = rope.link_anchor(RopeEnd::Start);
+        let end_anchor = rope.link_anchor(RopeEnd::End);
+        rope.joints = rope
+            .links
+            .windows(2)
+            .map(|pair| {
+                let params = rope.joint_params(end_anchor, start_anchor);
+                commands
+                    .spawn_bundle((JointBuilderComponent::new(params, pair[0], pair[1]),))
+                    .id()
+            })
+            .collect();
+
+        for (anchor, end, point) in [
+            (&builder.start, RopeEnd::Start, start),
+            (&builder.end, RopeEnd::End, end),
+        ]
+        .iter()
+        {
+            let (body, local_anchor, pin) = match anchor {
+                RopeAnchor::Point(_) => {
+                    let pin = commands
+                        .spawn_bundle(RigidBodyBundle {
+                            body_type: RigidBodyType::Static,
+                            position: Isometry::from_parts(point.coords.into(), rotation).into(),
+                            ..Default::default()
+                        })
+                        .id();
+                    (pin, Point::origin(), Some(pin))
+                }
+                RopeAnchor::Body(body, local_anchor) => (*body, *local_anchor, None),
+                RopeAnchor::Free(_) => continue,
+            };
+
+            let link = rope.end_link(*end).unwrap();
+            let joint = rope.attachment_joint(&mut commands, *end, link, body, local_anchor);
+            let attachment = Some(RopeAttachment { joint, pin });
+
+            match end {
+                RopeEnd::Start => rope.start = attachment,
+                RopeEnd::End => rope.end = attachment,
+            }
+        }
+
+        commands.entity(entity).remove::<RopeBuilder>().insert(rope);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_relative_eq(p1: Point<Real>, p2: Point<Real>) {
+        assert!((p1 - p2).norm() < 1.0e-5, "{:?} != {:?}", p1, p2);
+    }
+
+    #[test]
+    fn links_are_laid_out_end_to_end() {
+        let start = Point::from(Vector::repeat(1.0));
+        let end = start + Vector::x() * 6.0 - Vector::y() * 8.0;
+        let (positions, half_length) = link_layout(&start, &end, 4);
+        let (link_start, link_end) = (-Vector::y() * half_length, Vector::y() * half_length);
+
+        assert_eq!(positions.len(), 4);
+        assert!((half_length - 1.25).abs() < 1.0e-6);
+        assert_relative_eq(positions[0] * Point::from(link_start), start);
+        assert_relative_eq(positions[3] * Point::from(link_end), end);
+
+        for pair in positions.windows(2) {
+            assert_relative_eq(
+                pair[0] * Point::from(link_end),
+                pair[1] * Point::from(link_start),
+            );
+        }
+    }
+
+    #[test]
+    fn degenerate_layouts() {
+        let start = Point::from(Vector::repeat(1.0));
+
+        // A rope with coincident anchors hangs down.
+        let (positions, half_length) = link_layout(&start, &start, 2);
+        assert_eq!(positions.len(), 2);
+        assert_eq!(half_length, 0.0);
+        assert_relative_eq(positions[0] * Point::from(Vector::y()), start - Vector::y());
+
+        // A rope has at least one link.
+        let (positions, half_length) = link_layout(&start, &(start + Vector::x() * 2.0), 0);
+        assert_eq!(positions.len(), 1);
+        assert!((half_length - 1.0).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn link_rotations() {
+        let dirs = [
+            Vector::x(),
+            Vector::y(),
+            -Vector::y(),
+            Vector::repeat(1.0).normalize(),
+        ];
+
+        for dir in dirs.iter() {
+            assert!((link_rotation(dir) * Vector::y() - dir).norm() < 1.0e-5);
+        }
+    }
+}
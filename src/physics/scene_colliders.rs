@@ -0,0 +1,240 @@
+use crate::physics::{physics_isometry, AsyncCollider, MeshColliderShape, RapierConfiguration};
+use crate::rapier::dynamics::RigidBodyPosition;
+use crate::rapier::geometry::{
+    ColliderBroadPhaseData, ColliderChanges, ColliderFlags, ColliderMassProps, ColliderMaterial,
+    ColliderPosition, ColliderType,
+};
+use crate::rapier::math::{Real, Vector};
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+/// The entities that may be new nodes of a scene with a `SceneColliders` component.
+pub type NewSceneNodesFilter = Or<(Added<Name>, Changed<Parent>, Added<SceneColliders>)>;
+
+/// A naming convention turning the meshes of the matching nodes of a scene into colliders.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SceneColliderConvention {
+    /// The suffix of the names of the matching nodes, e.g., `"_col"`.
+    pub suffix: String,
+    /// The kind of shape built from the meshes of the matching nodes.
+    pub shape: MeshColliderShape,
+    /// Whether the colliders are sensors.
+    pub sensor: bool,
+}
+
+impl SceneColliderConvention {
+    /// A convention building solid colliders of the given kind for the nodes with the given
+    /// name suffix.
+    pub fn new(suffix: impl Into<String>, shape: MeshColliderShape) -> Self {
+        Self {
+            suffix: suffix.into(),
+            shape,
+            sensor: false,
+        }
+    }
+
+    /// Sets whether the colliders are sensors.
+    pub fn sensor(mut self, sensor: bool) -> Self {
+        self.sensor = sensor;
+        self
+    }
+
+    /// Does a node with the given name match this convention?
+    ///
+    /// The numbered suffix added by Blender to duplicated objects (e.g. `.001`) is ignored.
+    pub fn matches(&self, name: &str) -> bool {
+        let name = match name.rfind('.') {
+            Some(i) if name[i + 1..].chars().all(|c| c.is_ascii_digit()) => &name[..i],
+            _ => name,
+        };
+        name.ends_with(self.suffix.as_str())
+    }
+}
+
+/// A component turning the nodes of the scene spawned below its entity into colliders,
+/// following naming conventions, so collision shapes can be authored with the scene, e.g.,
+/// in Blender.
+///
+/// The meshes of each node whose `Name` matches one of the conventions are given an
+/// `AsyncCollider`, and their `Visible` component is hidden by default. By default:
+/// - `*_col` nodes become trimesh colliders.
+/// - `*_convex` nodes become convex hull colliders, usable by dynamic rigid-bodies.
+/// - `*_trigger` nodes become convex hull sensors.
+///
+/// Like any collider, each collider is attached to the rigid-body of its closest ancestor,
+/// which must exist when the scene is spawned, e.g., on the entity of this component. The
+/// colliders without rigid-body are fixed at their position in the scene. The vertices of the
+/// meshes are scaled by the scale of the nodes, and divided by `RapierConfiguration::scale`
+/// like the positions of the colliders.
+///
+/// The glTF extras of the nodes are not loaded by Bevy, so only their names are used.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SceneColliders {
+    /// The naming conventions, in priority order.
+    pub conventions: Vec<SceneColliderConvention>,
+    /// The density of the colliders.
+    pub density: Real,
+    /// Whether the meshes turned into colliders are hidden.
+    pub hide_meshes: bool,
+}
+
+impl Default for SceneColliders {
+    fn default() -> Self {
+        Self {
+            conventions: vec![
+                SceneColliderConvention::new("_col", MeshColliderShape::TriMesh),
+                SceneColliderConvention::new("_convex", MeshColliderShape::ConvexHull),
+                SceneColliderConvention::new("_trigger", MeshColliderShape::ConvexHull)
+                    .sensor(true),
+            ],
+            density: 1.0,
+            hide_meshes: true,
+        }
+    }
+}
+
+impl SceneColliders {
+    /// Scene colliders without any naming convention.
+    pub fn new() -> Self {
+        Self {
+            conventions: vec![],
+            ..Default::default()
+        }
+    }
+
+    /// Adds a naming convention, with a lower priority than the previous ones.
+    pub fn convention(mut self, convention: SceneColliderConvention) -> Self {
+        self.conventions.push(convention);
+        self
+    }
+
+    /// Sets the density of the colliders.
+    pub fn density(mut self, density: Real) -> Self {
+        self.density = density;
+        self
+    }
+
+    /// Sets whether the meshes turned into colliders are hidden.
+    pub fn hide_meshes(mut self, hide: bool) -> Self {
+        self.hide_meshes = hide;
+        self
+    }
+
+    fn convention_for(&self, name: &str) -> Option<&SceneColliderConvention> {
+        self.conventions
+            .iter()
+            .find(|convention| convention.matches(name))
+    }
+}
+
+/// The position of `entity` relative to the closest of its ancestors (possibly itself) with a
+/// rigid-body, or its world position if there is none.
+fn position_wrt_body(
+    entity: Entity,
+    transforms: &Query<&Transform>,
+    parents: &Query<&Parent>,
+    bodies: &Query<&RigidBodyPosition>,
+) -> GlobalTransform {
+    if bodies.get(entity).is_ok() {
+        return GlobalTransform::identity();
+    }
+
+    let local = transforms.get(entity).copied().unwrap_or_default();
+
+    match parents.get(entity) {
+        Ok(parent) => position_wrt_body(parent.0, transforms, parents, bodies).mul_transform(local),
+        Err(_) => GlobalTransform::from(local),
+    }
+}
+
+/// System responsible for creating the colliders of the nodes of the scenes with a
+/// `SceneColliders` component.
+pub fn create_scene_colliders_system(
+    mut commands: Commands,
+    configuration: Res<RapierConfiguration>,
+    new_nodes: Query<Entity, NewSceneNodesFilter>,
+    (names, children, transforms, parents): (
+        Query<&Name>,
+        Query<&Children>,
+        Query<&Transform>,
+        Query<&Parent>,
+    ),
+    (scenes, bodies): (Query<&SceneColliders>, Query<&RigidBodyPosition>),
+    meshes: Query<&Handle<Mesh>, Without<AsyncCollider>>,
+    mut visibles: Query<&mut Visible>,
+) {
+    // The nodes spawned, or moved, below a `SceneColliders` since the last frame.
+    let mut visited = HashSet::new();
+    let mut stack: Vec<_> = new_nodes.iter().collect();
+
+    while let Some(entity) = stack.pop() {
+        if visited.insert(entity) {
+            stack.extend(children.get(entity).into_iter().flat_map(|c| c.iter()));
+        }
+    }
+
+    for node in visited {
+        let name = match names.get(node) {
+            Ok(name) => name,
+            Err(_) => continue,
+        };
+
+        // Find the closest ancestor with a `SceneColliders`.
+        let mut ancestor = node;
+        let scene = loop {
+            if let Ok(scene) = scenes.get(ancestor) {
+                break Some(scene);
+            }
+
+            match parents.get(ancestor) {
+                Ok(&Parent(parent)) => ancestor = parent,
+                Err(_) => break None,
+            }
+        };
+
+        let (scene, convention) =
+            match scene.and_then(|scene| Some((scene, scene.convention_for(name.as_str())?))) {
+                Some(found) => found,
+                None => continue,
+            };
+
+        // The meshes of a glTF node are spawned as its children, one per primitive.
+        let node_children = children.get(node).into_iter().flat_map(|c| c.iter());
+
+        for entity in std::iter::once(&node).chain(node_children) {
+            let mesh = match meshes.get(*entity) {
+                Ok(mesh) => mesh,
+                Err(_) => continue,
+            };
+
+            let transform = position_wrt_body(*entity, &transforms, &parents, &bodies);
+            let position = physics_isometry(&transform, configuration.scale);
+            let scale = Vector::from(transform.scale) / configuration.scale;
+            let collider_type = if convention.sensor {
+                ColliderType::Sensor
+            } else {
+                ColliderType::Solid
+            };
+
+            // The shape is built by the `AsyncCollider` once the mesh is loaded.
+            commands
+                .entity(*entity)
+                .insert_bundle((
+                    collider_type,
+                    ColliderPosition::from(position),
+                    ColliderMaterial::default(),
+                    ColliderFlags::default(),
+                    ColliderMassProps::Density(scene.density),
+                    ColliderChanges::default(),
+                    ColliderBroadPhaseData::default(),
+                ))
+                .insert(AsyncCollider::new(mesh.clone(), convention.shape).scale(scale));
+
+            if scene.hide_meshes {
+                if let Ok(mut visible) = visibles.get_mut(*entity) {
+                    visible.is_visible = false;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use crate::physics::{
+    cast_rays_batched, physics_isometry, IntoEntity, QueryPipelineColliderComponentsQuery,
+    QueryPipelineColliderComponentsSet, RapierConfiguration, RayCastQuery,
+};
+use crate::rapier::dynamics::RigidBodyIds;
+use crate::rapier::geometry::{
+    ColliderHandle, ColliderParent, ColliderShape, InteractionGroups, Ray,
+};
+use crate::rapier::math::{Isometry, Point, Real, Vector};
+use crate::rapier::pipeline::QueryPipeline;
+use bevy::prelude::*;
+use bevy::tasks::ComputeTaskPool;
+
+/// The closest hit detected by a `RayCastSensor` or a `ShapeCastSensor`.
+///
+/// The point and normal are expressed in the local frame of the sensor's entity.
+#[derive(Copy, Clone, Debug)]
+pub struct CastSensorHit {
+    /// The entity of the collider that was hit.
+    pub entity: Entity,
+    /// The time-of-impact of the cast, in multiples of the sensor's `dir`.
+    pub toi: Real,
+    /// The distance traveled until the hit, i.e., `toi * dir.norm()`, in physics units.
+    pub distance: Real,
+    /// The hit point, expressed in the local frame of the sensor's entity.
+    pub point: Point<Real>,
+    /// The normal of the hit collider at the hit point, expressed in the local frame of the
+    /// sensor's entity.
+    pub normal: Vector<Real>,
+}
+
+/// A component casting a ray from its entity after each physics step.
+///
+/// The ray origin and direction are expressed in the local frame given by the entity's
+/// `GlobalTransform`, in physics units.
+/// The colliders attached to the sensor's own rigid-body (if any) are ignored.
+#[derive(Clone, Debug)]
+pub struct RayCastSensor {
+    /// The origin of the ray, in the local frame of the entity.
+    pub origin: Point<Real>,
+    /// The direction of the ray, in the local frame of the entity.
+    pub dir: Vector<Real>,
+    /// The maximum time-of-impact that can be reported by this sensor.
+    pub max_toi: Real,
+    /// If `true`, a hit at time 0.0 is reported if the ray starts inside of a shape.
+    pub solid: bool,
+    /// The interaction groups used to filter the colliders detected by this sensor.
+    pub groups: InteractionGroups,
+    /// The closest hit detected during the last update, if any.
+    pub hit: Option<CastSensorHit>,
+}
+
+impl RayCastSensor {
+    /// Initializes a solid ray-cast sensor interacting with all the collision groups.
+    pub fn new(origin: Point<Real>, dir: Vector<Real>, max_toi: Real) -> Self {
+        Self {
+            origin,
+            dir,
+            max_toi,
+            solid: true,
+            groups: InteractionGroups::all(),
+            hit: None,
+        }
+    }
+
+    /// Sets whether a hit at time 0.0 is reported when the ray starts inside of a shape.
+    pub fn solid(mut self, solid: bool) -> Self {
+        self.solid = solid;
+        self
+    }
+
+    /// Sets the interaction groups used to filter the colliders detected by this sensor.
+    pub fn groups(mut self, groups: InteractionGroups) -> Self {
+        self.groups = groups;
+        self
+    }
+}
+
+/// A component casting a shape from its entity after each physics step.
+///
+/// The shape position and the cast direction are expressed in the local frame given by the
+/// entity's `GlobalTransform`, in physics units. The colliders attached to the sensor's own
+/// rigid-body (if any) are ignored.
+#[derive(Clone)]
+pub struct ShapeCastSensor {
+    /// The shape to cast.
+    pub shape: ColliderShape,
+    /// The initial position of the shape, in the local frame of the entity.
+    pub shape_pos: Isometry<Real>,
+    /// The direction of the cast, in the local frame of the entity.
+    pub dir: Vector<Real>,
+    /// The maximum time-of-impact that can be reported by this sensor.
+    pub max_toi: Real,
+    /// The interaction groups used to filter the colliders detected by this sensor.
+    pub groups: InteractionGroups,
+    /// The closest hit detected during the last update, if any.
+    pub hit: Option<CastSensorHit>,
+}
+
+impl ShapeCastSensor {
+    /// Initializes a shape-cast sensor interacting with all the collision groups.
+    pub fn new(
+        shape: ColliderShape,
+        shape_pos: Isometry<Real>,
+        dir: Vector<Real>,
+        max_toi: Real,
+    ) -> Self {
+        Self {
+            shape,
+            shape_pos,
+            dir,
+            max_toi,
+            groups: InteractionGroups::all(),
+            hit: None,
+        }
+    }
+
+    /// Sets the interaction groups used to filter the colliders detected by this sensor.
+    pub fn groups(mut self, groups: InteractionGroups) -> Self {
+        self.groups = groups;
+        self
+    }
+}
+
+/// Finds the closest ancestor (possibly the same entity) with a rigid-body.
+fn find_body_entity(
+    entity: Entity,
+    bodies: &Query<(), With<RigidBodyIds>>,
+    parents: &Query<&Parent>,
+) -> Option<Entity> {
+    let mut curr = entity;
+    loop {
+        if bodies.get(curr).is_ok() {
+            return Some(curr);
+        } else if let Ok(&Parent(parent)) = parents.get(curr) {
+            curr = parent;
+        } else {
+            return None;
+        }
+    }
+}
+
+/// System responsible for updating the hits of the `RayCastSensor` and `ShapeCastSensor`
+/// components.
+pub fn update_cast_sensors_system(
+    (task_pool, configuration, query_pipeline): (
+        Res<ComputeTaskPool>,
+        Res<RapierConfiguration>,
+        Res<QueryPipeline>,
+    ),
+    colliders: QueryPipelineColliderComponentsQuery,
+    (collider_parents, bodies, parents): (
+        Query<&ColliderParent>,
+        Query<(), With<RigidBodyIds>>,
+        Query<&Parent>,
+    ),
+    mut ray_sensors: Query<(Entity, &GlobalTransform, &mut RayCastSensor)>,
+    mut shape_sensors: Query<(Entity, &GlobalTransform, &mut ShapeCastSensor)>,
+) {
+    let colliders = QueryPipelineColliderComponentsSet(&colliders);
+    let collider_body = |handle: ColliderHandle| {
+        collider_parents
+            .get(handle.entity())
+            .map(|co_parent| co_parent.handle.entity())
+            .unwrap_or(handle.entity())
+    };
+
+    // Ray-cast sensors are cast in parallel.
+    let mut rays = vec![];
+    let mut ray_entities = vec![];
+    let mut ray_frames = vec![];
+    let mut ray_excluded_bodies = vec![];
+
+    for (entity, transform, sensor) in ray_sensors.iter_mut() {
+        let pos = physics_isometry(transform, configuration.scale);
+        let ray = Ray::new(pos * sensor.origin, pos * sensor.dir);
+        rays.push(
+            RayCastQuery::new(ray, sensor.max_toi)
+                .solid(sensor.solid)
+                .groups(sensor.groups),
+        );
+        ray_entities.push(entity);
+        ray_frames.push(pos);
+        ray_excluded_bodies.push(find_body_entity(entity, &bodies, &parents));
+    }
+
+    let mut hits = vec![];
+    let filter =
+        |i: usize, handle: ColliderHandle| ray_excluded_bodies[i] != Some(collider_body(handle));
+    cast_rays_batched(
+        &task_pool,
+        &query_pipeline,
+        &colliders,
+        &rays,
+        Some(&filter),
+        &mut hits,
+    );
+
+    for (i, entity) in ray_entities.into_iter().enumerate() {
+        let pos = &ray_frames[i];
+        let (_, _, mut sensor) = ray_sensors.get_mut(entity).unwrap();
+        sensor.hit = hits[i].map(|(handle, inter)| CastSensorHit {
+            entity: handle.entity(),
+            toi: inter.toi,
+            distance: inter.toi * rays[i].ray.dir.norm(),
+            point: pos.inverse_transform_point(&rays[i].ray.point_at(inter.toi)),
+            normal: pos.inverse_transform_vector(&inter.normal),
+        });
+    }
+
+    for (entity, transform, mut sensor) in shape_sensors.iter_mut() {
+        let pos = physics_isometry(transform, configuration.scale);
+        let excluded_body = find_body_entity(entity, &bodies, &parents);
+        let filter = |handle: ColliderHandle| excluded_body != Some(collider_body(handle));
+        let hit = query_pipeline.cast_shape(
+            &colliders,
+            &(pos * sensor.shape_pos),
+            &(pos * sensor.dir),
+            &*sensor.shape,
+            sensor.max_toi,
+            sensor.groups,
+            Some(&filter),
+        );
+
+        sensor.hit = hit.map(|(handle, toi)| CastSensorHit {
+            entity: handle.entity(),
+            toi: toi.toi,
+            distance: toi.toi * sensor.dir.norm(),
+            point: pos.inverse_transform_point(&toi.witness1),
+            normal: pos.inverse_transform_vector(&toi.normal1),
+        });
+    }
+}
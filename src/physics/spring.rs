@@ -0,0 +1,268 @@
+use crate::physics::{wake_up_dynamic_body, IntoHandle, RigidBodyComponentsSet};
+use crate::rapier::data::{ComponentSet, ComponentSetMut, ComponentSetOption, Index};
+use crate::rapier::dynamics::{
+    IslandManager, RigidBodyActivation, RigidBodyForces, RigidBodyMassProps, RigidBodyPosition,
+    RigidBodyType, RigidBodyVelocity,
+};
+use crate::rapier::math::{Point, Real, Vector};
+use bevy::prelude::*;
+
+/// The fraction of the length limits violation corrected at each step.
+const LIMIT_ERP: Real = 0.2;
+
+/// A component applying the forces of a spring and damper to two rigid-bodies.
+///
+/// Unlike joints, springs are soft: they apply a force proportional to the difference between
+/// their length and their rest length, and to the speed at which their length changes. They
+/// can also be given a minimum and maximum length, enforced like a rope or a rod.
+///
+/// The spring is typically added to its own entity, like the `JointBuilderComponent`. Its
+/// forces are applied at each physics step (including each step performed during a single
+/// frame with the `TimestepMode::InterpolatedTimestep`).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Spring {
+    /// The entity of the first rigid-body attached to the spring.
+    pub entity1: Entity,
+    /// The entity of the second rigid-body attached to the spring.
+    pub entity2: Entity,
+    /// The point where the spring is attached to the first rigid-body, in its local frame.
+    pub local_anchor1: Point<Real>,
+    /// The point where the spring is attached to the second rigid-body, in its local frame.
+    pub local_anchor2: Point<Real>,
+    /// The length of the spring when it doesn't apply any force.
+    pub rest_length: Real,
+    /// The force applied by the spring per unit of length away from its rest length.
+    pub stiffness: Real,
+    /// The force applied by the spring per unit of speed at which its length changes.
+    pub damping: Real,
+    /// The minimum length of the spring.
+    pub min_length: Real,
+    /// The maximum length of the spring.
+    pub max_length: Real,
+}
+
+impl Spring {
+    /// A spring attached to the origin of both rigid-bodies, without length limits.
+    pub fn new(
+        entity1: Entity,
+        entity2: Entity,
+        rest_length: Real,
+        stiffness: Real,
+        damping: Real,
+    ) -> Self {
+        Self {
+            entity1,
+            entity2,
+            local_anchor1: Point::origin(),
+            local_anchor2: Point::origin(),
+            rest_length,
+            stiffness,
+            damping,
+            min_length: 0.0,
+            max_length: Real::MAX,
+        }
+    }
+
+    /// Sets the points where the spring is attached to the rigid-bodies, in their local frames.
+    pub fn local_anchors(mut self, local_anchor1: Point<Real>, local_anchor2: Point<Real>) -> Self {
+        self.local_anchor1 = local_anchor1;
+        self.local_anchor2 = local_anchor2;
+        self
+    }
+
+    /// Sets the minimum and maximum length of the spring.
+    pub fn limits(mut self, min_length: Real, max_length: Real) -> Self {
+        self.min_length = min_length;
+        self.max_length = max_length;
+        self
+    }
+
+    /// The magnitude of the force pulling the rigid-bodies together, given the length of the
+    /// spring and the speed at which it changes.
+    fn force(&self, length: Real, speed: Real) -> Real {
+        self.stiffness * (length - self.rest_length) + self.damping * speed
+    }
+
+    /// The speed at which the length must change to stay within the length limits at the end
+    /// of a step of length `dt`, if the current speed would violate them.
+    fn limit_speed(&self, length: Real, speed: Real, dt: Real) -> Option<Real> {
+        // Cancel the relative velocity that would bring the length outside of its limits
+        // at the end of the step, and correct the existing violations progressively.
+        if length + speed * dt > self.max_length {
+            let erp = if length > self.max_length {
+                LIMIT_ERP
+            } else {
+                1.0
+            };
+            Some((self.max_length - length) * erp / dt).filter(|target| speed > *target)
+        } else if length + speed * dt < self.min_length {
+            let erp = if length < self.min_length {
+                LIMIT_ERP
+            } else {
+                1.0
+            };
+            Some((self.min_length - length) * erp / dt).filter(|target| speed < *target)
+        } else {
+            None
+        }
+    }
+}
+
+/// The state of one of the rigid-bodies attached to a spring.
+struct SpringBody {
+    handle: Index,
+    anchor: Point<Real>,
+    velocity: Vector<Real>,
+    mprops: Option<RigidBodyMassProps>,
+}
+
+impl SpringBody {
+    fn new(bodies: &RigidBodyComponentsSet, handle: Index, local_anchor: &Point<Real>) -> Self {
+        let rb_pos: &RigidBodyPosition = bodies.index(handle);
+        let rb_vels: &RigidBodyVelocity = bodies.index(handle);
+        let mut rb_mprops: RigidBodyMassProps = *bodies.index(handle);
+        rb_mprops.update_world_mass_properties(&rb_pos.position);
+        let anchor = rb_pos.position * local_anchor;
+
+        Self {
+            handle,
+            anchor,
+            velocity: rb_vels.velocity_at_point(&anchor, &rb_mprops.world_com),
+            mprops: None,
+        }
+    }
+
+    /// Is this rigid-body moving, or susceptible to move?
+    fn is_active(&self, bodies: &RigidBodyComponentsSet) -> bool {
+        let rb_type: &RigidBodyType = bodies.index(self.handle);
+        let rb_activation: &RigidBodyActivation = bodies.index(self.handle);
+
+        if rb_type.is_dynamic() {
+            !rb_activation.sleeping
+        } else {
+            self.velocity != Vector::zeros()
+        }
+    }
+
+    /// The change of velocity at the anchor, along `dir`, caused by a unit impulse along `dir`.
+    fn inv_mass_along(&self, dir: &Vector<Real>) -> Real {
+        match &self.mprops {
+            Some(mprops) => {
+                let mut rb_vels = RigidBodyVelocity::default();
+                rb_vels.apply_impulse_at_point(mprops, *dir, self.anchor);
+                rb_vels
+                    .velocity_at_point(&self.anchor, &mprops.world_com)
+                    .dot(dir)
+            }
+            None => 0.0,
+        }
+    }
+
+    fn apply_force(&self, bodies: &mut RigidBodyComponentsSet, force: Vector<Real>) {
+        if let Some(mprops) = &self.mprops {
+            bodies.map_mut_internal(self.handle, |rb_forces: &mut RigidBodyForces| {
+                rb_forces.apply_force_at_point(mprops, force, self.anchor)
+            });
+        }
+    }
+
+    fn apply_impulse(&self, bodies: &mut RigidBodyComponentsSet, impulse: Vector<Real>) {
+        if let Some(mprops) = &self.mprops {
+            bodies.map_mut_internal(self.handle, |rb_vels: &mut RigidBodyVelocity| {
+                rb_vels.apply_impulse_at_point(mprops, impulse, self.anchor)
+            });
+        }
+    }
+}
+
+/// Applies the forces of the `Spring`s to their rigid-bodies, and enforces their length limits.
+pub(crate) fn apply_springs(
+    dt: Real,
+    islands: &mut IslandManager,
+    bodies: &mut RigidBodyComponentsSet,
+    springs: &Query<&Spring>,
+) {
+    if dt <= 0.0 {
+        return;
+    }
+
+    for spring in springs.iter() {
+        let (handle1, handle2): (Index, Index) = (spring.entity1.handle(), spring.entity2.handle());
+        let (rb_pos1, rb_pos2): (Option<&RigidBodyPosition>, Option<&RigidBodyPosition>) =
+            (bodies.get(handle1), bodies.get(handle2));
+        if rb_pos1.is_none() || rb_pos2.is_none() {
+            continue;
+        }
+
+        let mut body1 = SpringBody::new(bodies, handle1, &spring.local_anchor1);
+        let mut body2 = SpringBody::new(bodies, handle2, &spring.local_anchor2);
+
+        // A spring between two sleeping bodies doesn't wake them up.
+        if !body1.is_active(bodies) && !body2.is_active(bodies) {
+            continue;
+        }
+
+        body1.mprops = wake_up_dynamic_body(islands, bodies, handle1);
+        body2.mprops = wake_up_dynamic_body(islands, bodies, handle2);
+
+        let delta = body2.anchor - body1.anchor;
+        let length = delta.norm();
+        let dir = match delta.try_normalize(1.0e-6) {
+            Some(dir) => dir,
+            None => continue,
+        };
+        let speed = (body2.velocity - body1.velocity).dot(&dir);
+
+        let force = dir * spring.force(length, speed);
+        body1.apply_force(bodies, force);
+        body2.apply_force(bodies, -force);
+
+        if let Some(target_speed) = spring.limit_speed(length, speed, dt) {
+            let inv_mass = body1.inv_mass_along(&dir) + body2.inv_mass_along(&dir);
+            if inv_mass > 0.0 {
+                let impulse = dir * ((speed - target_speed) / inv_mass);
+                body1.apply_impulse(bodies, impulse);
+                body2.apply_impulse(bodies, -impulse);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spring() -> Spring {
+        Spring::new(Entity::new(0), Entity::new(1), 2.0, 10.0, 0.5).limits(1.0, 3.0)
+    }
+
+    #[test]
+    fn force() {
+        let spring = spring();
+        assert_eq!(spring.force(2.0, 0.0), 0.0);
+        // A stretched spring pulls the bodies together, a compressed one pushes them apart.
+        assert_eq!(spring.force(2.5, 0.0), 5.0);
+        assert_eq!(spring.force(1.5, 0.0), -5.0);
+        // The damping opposes the change of length.
+        assert_eq!(spring.force(2.0, 4.0), 2.0);
+        assert_eq!(spring.force(2.0, -4.0), -2.0);
+    }
+
+    #[test]
+    fn limit_speed() {
+        let spring = spring();
+        let dt = 0.1;
+        assert_eq!(spring.limit_speed(2.0, 1.0, dt), None);
+        // The speed is reduced to reach the limit exactly at the end of the step.
+        assert_eq!(spring.limit_speed(2.5, 10.0, dt), Some(5.0));
+        assert_eq!(spring.limit_speed(1.5, -10.0, dt), Some(-5.0));
+        // The existing violations are corrected progressively.
+        assert_eq!(
+            spring.limit_speed(3.5, 0.0, dt),
+            Some(-0.5 * LIMIT_ERP / dt)
+        );
+        assert_eq!(spring.limit_speed(0.5, 0.0, dt), Some(0.5 * LIMIT_ERP / dt));
+        // Unless the bodies are already moving back within the limits fast enough.
+        assert_eq!(spring.limit_speed(3.5, -10.0, dt), None);
+    }
+}
@@ -1,39 +1,62 @@
 use crate::physics::{
+    apply_aerodynamics, apply_external_forces, apply_external_impulses, apply_fluid_volumes,
+    apply_gravity_fields, apply_gravity_scales, apply_joint_motor_max_forces, apply_springs,
+    apply_velocity_limits, reset_removed_gravity_scales, update_joint_reactions, AerodynamicsQuery,
     ColliderComponentsQuery, ColliderComponentsSet, ColliderPositionSync, ComponentSetQueryMut,
-    EventQueue, IntoEntity, IntoHandle, JointBuilderComponent, JointHandleComponent,
+    EventQueue, ExternalForcesQuery, FluidVolume, GravityQuery, IntoEntity, IntoHandle,
+    JointBuilderComponent, JointContactFilter, JointCreationError, JointCreationErrorKind,
+    JointHandleComponent, JointMotor, JointParamsComponent, JointReactionsQuery, Joints,
     JointsEntityMap, ModificationTracker, PhysicsHooksWithQueryInstance,
     PhysicsHooksWithQueryObject, QueryComponentSetMut, RapierConfiguration,
     RigidBodyComponentsQuery, RigidBodyComponentsSet, RigidBodyPositionSync,
-    SimulationToRenderTime, TimestepMode,
+    SimulationToRenderTime, Spring, TimestepMode, VelocityLimits,
 };
+#[cfg(feature = "dim3")]
+use crate::physics::{update_vehicle_wheels, VehicleWheelsQuery};
 
 use crate::prelude::{ContactEvent, IntersectionEvent};
-use crate::rapier::data::ComponentSetOption;
+use crate::rapier::data::{ComponentSetMut, ComponentSetOption};
 use crate::rapier::dynamics::{
-    RigidBodyCcd, RigidBodyChanges, RigidBodyColliders, RigidBodyIds, RigidBodyMassProps,
-    RigidBodyPosition,
+    RigidBodyActivation, RigidBodyCcd, RigidBodyChanges, RigidBodyColliders, RigidBodyHandle,
+    RigidBodyIds, RigidBodyMassProps, RigidBodyPosition,
 };
 use crate::rapier::geometry::{
-    ColliderBroadPhaseData, ColliderChanges, ColliderMassProps, ColliderParent, ColliderPosition,
-    ColliderShape,
+    ColliderBroadPhaseData, ColliderChanges, ColliderFlags, ColliderHandle, ColliderMassProps,
+    ColliderParent, ColliderPosition, ColliderShape,
 };
-use crate::rapier::pipeline::QueryPipeline;
+use crate::rapier::pipeline::{ActiveHooks, QueryPipeline};
+use bevy::ecs::entity::Entities;
 use bevy::ecs::query::WorldQuery;
 use bevy::prelude::*;
-use rapier::dynamics::{CCDSolver, IntegrationParameters, IslandManager, JointSet};
+use rapier::dynamics::{
+    CCDSolver, IntegrationParameters, IslandManager, JointHandle, JointParams, JointSet,
+};
 use rapier::geometry::{BroadPhase, NarrowPhase};
-use rapier::math::Isometry;
+use rapier::math::{Isometry, Real};
 use rapier::pipeline::PhysicsPipeline;
+use std::collections::HashSet;
 use std::sync::RwLock;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
 pub enum PhysicsSystems {
     AttachBodiesAndColliders,
     FinalizeColliderAttachToBodies,
+    CreateParentJoints,
     CreateJoints,
+    SyncJointParams,
+    SyncJointMotors,
+    SyncJointContacts,
     StepWorld,
     SyncTransforms,
     CollectRemovals,
+    UpdateCastSensors,
+    UpdateCharacterControllers,
+    UpdateMovingPlatforms,
+    CreateRagdolls,
+    UpdateRagdolls,
+    CreateRopes,
+    CreateAsyncColliders,
+    CreateSceneColliders,
 }
 
 /// System responsible for creating a Rapier rigid-body and collider from their
@@ -42,6 +65,7 @@ pub fn attach_bodies_and_colliders_system(
     mut commands: Commands,
     body_query: Query<&RigidBodyPosition>,
     parent_query: Query<&Parent>,
+    shape_query: Query<&ColliderShape>,
     mut colliders_query: Query<
         (
             Entity,
@@ -52,6 +76,11 @@ pub fn attach_bodies_and_colliders_system(
     >,
 ) {
     'outer: for (collider_entity, co_pos) in colliders_query.iter_mut() {
+        // Wait for the shape of colliders built asynchronously, e.g., by an `AsyncCollider`.
+        if shape_query.get(collider_entity).is_err() {
+            continue;
+        }
+
         // Find the closest ancestor (possibly the same entity) with a body
         let mut body_entity = collider_entity;
         loop {
@@ -150,38 +179,113 @@ pub fn finalize_collider_attach_to_bodies(
     }
 }
 
+/// The state of a joint described by a `JointBuilderComponent`.
+enum JointBuilderStatus {
+    /// The rigid-bodies of the joint exist.
+    Ready,
+    /// The joint is waiting for the rigid-bodies of its entities.
+    Pending,
+    /// The joint can't be created.
+    Abandoned(Entity, JointCreationErrorKind),
+}
+
+/// Checks if the rigid-bodies of a joint exist, and updates its pending state.
+fn joint_builder_status<Bodies>(
+    joint: &mut JointBuilderComponent,
+    timeout: Option<u32>,
+    entities: &Entities,
+    bodies: &Bodies,
+) -> JointBuilderStatus
+where
+    Bodies: ComponentSetOption<RigidBodyIds>,
+{
+    // Report the despawned entities first since the joint can't be created in this case.
+    let error = [joint.entity1, joint.entity2]
+        .iter()
+        .filter_map(|body| {
+            let handle: RigidBodyHandle = body.handle();
+            if !entities.contains(*body) {
+                Some((*body, JointCreationErrorKind::EntityNotFound))
+            } else if bodies.get(handle.0).is_none() {
+                Some((*body, JointCreationErrorKind::RigidBodyNotFound))
+            } else {
+                None
+            }
+        })
+        .min_by_key(|(_, kind)| *kind != JointCreationErrorKind::EntityNotFound);
+
+    match error {
+        None => JointBuilderStatus::Ready,
+        Some((body, kind)) => {
+            let timed_out = timeout
+                .map(|timeout| joint.pending_frames >= timeout)
+                .unwrap_or(false);
+
+            if kind == JointCreationErrorKind::EntityNotFound || timed_out {
+                JointBuilderStatus::Abandoned(body, kind)
+            } else {
+                joint.pending_frames += 1;
+                JointBuilderStatus::Pending
+            }
+        }
+    }
+}
+
+/// Adds the joint described by a builder to the `JointSet`.
+fn insert_joint<Bodies>(
+    joints: &mut JointSet,
+    joint_contacts: &mut JointContactFilter,
+    bodies: &mut Bodies,
+    joint: &JointBuilderComponent,
+) -> JointHandle
+where
+    Bodies: ComponentSetMut<RigidBodyIds>,
+{
+    let (body1, body2) = (joint.entity1.handle(), joint.entity2.handle());
+    let handle = joints.insert(bodies, body1, body2, joint.params);
+
+    if !joint.contacts_enabled {
+        joint_contacts.disable(handle, body1, body2);
+    }
+
+    handle
+}
+
 /// System responsible for creating Rapier joints from their builder resources.
+///
+/// The joints are described by the `JointBuilderComponent`s and the `Joints` components.
 pub fn create_joints_system(
     mut commands: Commands,
-    mut joints: ResMut<JointSet>,
-    mut joints_entity_map: ResMut<JointsEntityMap>,
-    query: Query<(Entity, &JointBuilderComponent)>,
+    (configuration, entities): (Res<RapierConfiguration>, &Entities),
+    (mut joints, mut joints_entity_map, mut joint_contacts): (
+        ResMut<JointSet>,
+        ResMut<JointsEntityMap>,
+        ResMut<JointContactFilter>,
+    ),
+    mut errors: EventWriter<JointCreationError>,
+    mut builders: Query<(Entity, &mut JointBuilderComponent)>,
+    mut joint_lists: Query<(Entity, &mut Joints)>,
     bodies: ComponentSetQueryMut<RigidBodyIds>,
 ) {
     let mut bodies = QueryComponentSetMut(bodies);
+    let timeout = configuration.joint_creation_timeout;
 
-    for (entity, joint) in &mut query.iter() {
-        // Make sure the rigid-bodies the joint it attached to exist.
-        if bodies
-            .0
-            .q0()
-            .get_component::<RigidBodyIds>(joint.entity1)
-            .is_err()
-            || bodies
-                .0
-                .q0()
-                .get_component::<RigidBodyIds>(joint.entity2)
-                .is_err()
-        {
-            continue;
+    for (entity, mut joint) in builders.iter_mut() {
+        match joint_builder_status(&mut joint, timeout, entities, &bodies) {
+            JointBuilderStatus::Ready => {}
+            JointBuilderStatus::Pending => continue,
+            JointBuilderStatus::Abandoned(body, kind) => {
+                errors.send(JointCreationError {
+                    joint: entity,
+                    entity: body,
+                    kind,
+                });
+                commands.entity(entity).remove::<JointBuilderComponent>();
+                continue;
+            }
         }
 
-        let handle = joints.insert(
-            &mut bodies,
-            joint.entity1.handle(),
-            joint.entity2.handle(),
-            joint.params,
-        );
+        let handle = insert_joint(&mut joints, &mut joint_contacts, &mut bodies, &joint);
         commands
             .entity(entity)
             .insert(JointHandleComponent::new(
@@ -189,11 +293,241 @@ pub fn create_joints_system(
                 joint.entity1,
                 joint.entity2,
             ))
+            .insert(JointParamsComponent(joint.params))
             .remove::<JointBuilderComponent>();
-        joints_entity_map.0.insert(entity, handle);
+        joints_entity_map.joints.insert(entity, handle);
+    }
+
+    for (entity, mut list) in joint_lists.iter_mut() {
+        // Forget the joints removed with one of their rigid-bodies.
+        if list
+            .handles
+            .iter()
+            .any(|handle| joints.get(handle.handle()).is_none())
+        {
+            list.handles
+                .retain(|handle| joints.get(handle.handle()).is_some());
+        }
+
+        if list.builders.is_empty() {
+            continue;
+        }
+
+        let mut pending = vec![];
+
+        for mut joint in std::mem::take(&mut list.builders) {
+            match joint_builder_status(&mut joint, timeout, entities, &bodies) {
+                JointBuilderStatus::Ready => {
+                    let handle =
+                        insert_joint(&mut joints, &mut joint_contacts, &mut bodies, &joint);
+                    list.handles.push(JointHandleComponent::new(
+                        handle,
+                        joint.entity1,
+                        joint.entity2,
+                    ));
+                    joints_entity_map
+                        .joint_lists
+                        .entry(entity)
+                        .or_default()
+                        .push(handle);
+                }
+                JointBuilderStatus::Pending => pending.push(joint),
+                JointBuilderStatus::Abandoned(body, kind) => errors.send(JointCreationError {
+                    joint: entity,
+                    entity: body,
+                    kind,
+                }),
+            }
+        }
+
+        list.builders = pending;
+    }
+}
+
+/// The given joint parameters, with the impulses accumulated by the solver, and the state of the
+/// motor, of the live joint, so its warm-starting isn't reset.
+fn with_live_joint_state(params: JointParams, live: &JointParams) -> JointParams {
+    match (params, live) {
+        (JointParams::BallJoint(mut params), JointParams::BallJoint(live)) => {
+            params.impulse = live.impulse;
+            params.motor_impulse = live.motor_impulse;
+            params.into()
+        }
+        (JointParams::FixedJoint(mut params), JointParams::FixedJoint(live)) => {
+            params.impulse = live.impulse;
+            params.into()
+        }
+        (JointParams::PrismaticJoint(mut params), JointParams::PrismaticJoint(live)) => {
+            params.impulse = live.impulse;
+            params.limits_impulse = live.limits_impulse;
+            params.motor_impulse = live.motor_impulse;
+            params.into()
+        }
+        #[cfg(feature = "dim3")]
+        (JointParams::RevoluteJoint(params), JointParams::RevoluteJoint(live)) => {
+            // Part of the motor state is private, so the configuration is copied into the live
+            // joint instead.
+            let mut joint = *live;
+            joint.local_anchor1 = params.local_anchor1;
+            joint.local_anchor2 = params.local_anchor2;
+            joint.local_axis1 = params.local_axis1;
+            joint.local_axis2 = params.local_axis2;
+            joint.basis1 = params.basis1;
+            joint.basis2 = params.basis2;
+            joint.motor_target_vel = params.motor_target_vel;
+            joint.motor_target_pos = params.motor_target_pos;
+            joint.motor_stiffness = params.motor_stiffness;
+            joint.motor_damping = params.motor_damping;
+            joint.motor_max_impulse = params.motor_max_impulse;
+            joint.motor_model = params.motor_model;
+            joint.into()
+        }
+        // The joint type changed, so there is no state to keep.
+        (params, _) => params,
+    }
+}
+
+/// System responsible for applying the modifications of the `JointParamsComponent`s to the
+/// joints of the `JointSet` resource.
+pub fn sync_joint_params_system(
+    mut joints: ResMut<JointSet>,
+    query: Query<(&JointHandleComponent, &JointParamsComponent), Changed<JointParamsComponent>>,
+    mut activations: Query<&mut RigidBodyActivation>,
+) {
+    for (handle, params) in query.iter() {
+        if let Some(joint) = joints.get_mut(handle.handle()) {
+            joint.params = with_live_joint_state(params.0, &joint.params);
+
+            for entity in [handle.entity1(), handle.entity2()].iter() {
+                if let Ok(mut activation) = activations.get_mut(*entity) {
+                    activation.wake_up(true);
+                }
+            }
+        }
     }
 }
 
+/// System responsible for enabling the contact filtering hooks on the colliders of the
+/// rigid-bodies attached by joints with their contacts disabled.
+pub fn sync_joint_contacts_system(
+    joints: Res<JointSet>,
+    mut joint_contacts: ResMut<JointContactFilter>,
+    bodies: Query<&RigidBodyColliders>,
+    mut colliders: Query<&mut ColliderFlags>,
+) {
+    joint_contacts.retain_joints(&joints);
+
+    let joint_contacts = &mut *joint_contacts;
+    let hooked_colliders: HashSet<ColliderHandle> = joint_contacts
+        .pairs
+        .iter()
+        .flat_map(|(body1, body2)| vec![*body1, *body2])
+        .filter_map(|body| bodies.get(body.0.entity()).ok())
+        .flat_map(|rb_colliders| rb_colliders.0.iter().copied())
+        .collect();
+
+    for collider in &hooked_colliders {
+        if joint_contacts.hooked_colliders.contains(collider) {
+            continue;
+        }
+
+        if let Ok(mut co_flags) = colliders.get_mut(collider.0.entity()) {
+            // Leave the colliders with the hooks enabled by the user untouched. A flag enabled
+            // by the user after this point is indistinguishable from the plugin's.
+            if !co_flags
+                .active_hooks
+                .contains(ActiveHooks::FILTER_CONTACT_PAIRS)
+            {
+                co_flags.active_hooks |= ActiveHooks::FILTER_CONTACT_PAIRS;
+                joint_contacts.hooked_colliders.insert(*collider);
+            }
+        }
+    }
+
+    for collider in joint_contacts.hooked_colliders.iter() {
+        if !hooked_colliders.contains(collider) {
+            if let Ok(mut co_flags) = colliders.get_mut(collider.0.entity()) {
+                co_flags.active_hooks -= ActiveHooks::FILTER_CONTACT_PAIRS;
+            }
+        }
+    }
+
+    joint_contacts
+        .hooked_colliders
+        .retain(|collider| hooked_colliders.contains(collider));
+}
+
+/// The components applying forces to the rigid-bodies before each timestep.
+pub type ForceComponentsQuery<'a, 'b, 'c> = (
+    ExternalForcesQuery<'a, 'b, 'c>,
+    GravityQuery<'a, 'b, 'c>,
+    Query<'a, (Entity, &'b FluidVolume)>,
+    Query<'a, &'b Spring>,
+    AerodynamicsQuery<'a, 'b, 'c>,
+    Query<'a, (&'b JointHandleComponent, &'b JointMotor)>,
+);
+
+/// Applies the forces of the `ForceComponentsQuery` components, and of the vehicle wheels, to
+/// the rigid-bodies and joints before a timestep of length `dt`.
+///
+/// This must be called before each timestep because Rapier resets the forces applied to the
+/// rigid-bodies after each step. The helpers don't apply forces to the rigid-bodies that are
+/// asleep and stay asleep, since these forces would accumulate until they wake up.
+fn apply_forces_before_step(
+    dt: Real,
+    configuration: &RapierConfiguration,
+    (islands, narrow_phase, joints): (&mut IslandManager, &NarrowPhase, &mut JointSet),
+    (bodies, colliders): (&mut RigidBodyComponentsSet, &ColliderComponentsSet),
+    (
+        (external_forces, external_impulses),
+        (gravity_scales, _, gravity_fields),
+        fluid_volumes,
+        springs,
+        (aerodynamics, wind_fields),
+        joint_motors,
+    ): &mut ForceComponentsQuery,
+    #[cfg(feature = "dim3")] (query_pipeline, vehicle_wheels): (
+        &QueryPipeline,
+        &mut VehicleWheelsQuery,
+    ),
+) {
+    let field_position =
+        |transform: &GlobalTransform| physics_isometry(transform, configuration.scale);
+    let gravity_fields: Vec<_> = gravity_fields
+        .iter()
+        .map(|(transform, field)| (field_position(transform), field))
+        .collect();
+    let wind_fields: Vec<_> = wind_fields
+        .iter()
+        .map(|(transform, field)| (field_position(transform), field))
+        .collect();
+
+    apply_external_forces(islands, bodies, external_forces);
+    apply_external_impulses(islands, bodies, external_impulses);
+    apply_gravity_scales(islands, bodies, gravity_scales);
+    apply_gravity_fields(islands, bodies, &gravity_fields);
+    apply_fluid_volumes(
+        &configuration.gravity,
+        dt,
+        (islands, narrow_phase),
+        bodies,
+        colliders,
+        fluid_volumes,
+    );
+    apply_springs(dt, islands, bodies, springs);
+    apply_aerodynamics(dt, islands, bodies, colliders, aerodynamics, &wind_fields);
+    apply_joint_motor_max_forces(dt, joints, joint_motors);
+    #[cfg(feature = "dim3")]
+    update_vehicle_wheels(
+        dt,
+        query_pipeline,
+        islands,
+        bodies,
+        colliders,
+        vehicle_wheels,
+    );
+}
+
 /// System responsible for performing one timestep of the physics world.
 pub fn step_world_system<UserData: 'static + WorldQuery>(
     mut commands: Commands,
@@ -209,6 +543,7 @@ pub fn step_world_system<UserData: 'static + WorldQuery>(
         mut ccd_solver,
         mut joints,
         mut joints_entity_map,
+        joint_contacts,
     ): (
         ResMut<PhysicsPipeline>,
         ResMut<QueryPipeline>,
@@ -218,6 +553,7 @@ pub fn step_world_system<UserData: 'static + WorldQuery>(
         ResMut<CCDSolver>,
         ResMut<JointSet>,
         ResMut<JointsEntityMap>,
+        Res<JointContactFilter>,
     ),
     hooks: Res<PhysicsHooksWithQueryObject<UserData>>,
     (intersection_events, contact_events): (
@@ -228,10 +564,14 @@ pub fn step_world_system<UserData: 'static + WorldQuery>(
     mut position_sync_query: Query<(Entity, &mut RigidBodyPositionSync)>,
     bodies_query: RigidBodyComponentsQuery,
     colliders_query: ColliderComponentsQuery,
-    (removed_bodies, removed_colliders, removed_joints): (
+    mut forces: ForceComponentsQuery,
+    (velocity_limits, mut joint_reactions): (Query<(Entity, &VelocityLimits)>, JointReactionsQuery),
+    #[cfg(feature = "dim3")] mut vehicle_wheels: VehicleWheelsQuery,
+    (removed_bodies, removed_colliders, removed_joints, removed_joint_lists): (
         RemovedComponents<RigidBodyChanges>,
         RemovedComponents<ColliderChanges>,
         RemovedComponents<JointHandleComponent>,
+        RemovedComponents<Joints>,
     ),
 ) {
     use std::mem::replace;
@@ -243,7 +583,12 @@ pub fn step_world_system<UserData: 'static + WorldQuery>(
     let mut rigid_body_components_set = RigidBodyComponentsSet(bodies_query);
     let mut collider_components_set = ColliderComponentsSet(colliders_query);
 
-    modifs_tracker.detect_removals(removed_bodies, removed_colliders, removed_joints);
+    modifs_tracker.detect_removals(
+        removed_bodies,
+        removed_colliders,
+        removed_joints,
+        removed_joint_lists,
+    );
     modifs_tracker.detect_modifications(
         &mut rigid_body_components_set.0,
         &mut collider_components_set.0,
@@ -257,9 +602,18 @@ pub fn step_world_system<UserData: 'static + WorldQuery>(
     );
     islands.cleanup_removed_rigid_bodies(&mut rigid_body_components_set);
 
+    let (gravity_scales, removed_gravity_scales, _) = &forces.1;
+    reset_removed_gravity_scales(
+        &mut islands,
+        &mut rigid_body_components_set,
+        gravity_scales,
+        removed_gravity_scales,
+    );
+
     let physics_hooks = PhysicsHooksWithQueryInstance {
         user_data,
         hooks: &*hooks.0,
+        joint_contacts: &joint_contacts,
     };
 
     match configuration.timestep_mode {
@@ -272,7 +626,9 @@ pub fn step_world_system<UserData: 'static + WorldQuery>(
                     // NOTE: in this comparison we do the same computations we
                     // will do for the next `while` iteration test, to make sure we
                     // don't get bit by potential float inaccuracy.
-                    if sim_to_render_time.diff - sim_dt < sim_dt {
+                    let last_step = sim_to_render_time.diff - sim_dt < sim_dt;
+
+                    if last_step {
                         // This is the last simulation step to be executed in the loop
                         // Update the previous state transforms
                         for (entity, mut position_sync) in position_sync_query.iter_mut() {
@@ -288,6 +644,16 @@ pub fn step_world_system<UserData: 'static + WorldQuery>(
                         }
                     }
 
+                    apply_forces_before_step(
+                        integration_parameters.dt,
+                        &configuration,
+                        (&mut islands, &narrow_phase, &mut joints),
+                        (&mut rigid_body_components_set, &collider_components_set),
+                        &mut forces,
+                        #[cfg(feature = "dim3")]
+                        (&query_pipeline, &mut vehicle_wheels),
+                    );
+
                     pipeline.step_generic(
                         &configuration.gravity,
                         &integration_parameters,
@@ -305,7 +671,24 @@ pub fn step_world_system<UserData: 'static + WorldQuery>(
                         &events,
                     );
 
+                    apply_velocity_limits(&mut rigid_body_components_set, &velocity_limits);
+                    update_joint_reactions(
+                        integration_parameters.dt,
+                        &joints,
+                        &rigid_body_components_set,
+                        &mut joint_reactions,
+                    );
                     modifs_tracker.clear_modified_and_removed();
+
+                    // The forces of the next step, e.g., of the vehicle wheels, query the colliders
+                    // moved by this one.
+                    if !last_step && configuration.query_pipeline_active {
+                        query_pipeline.update_generic(
+                            &islands,
+                            &rigid_body_components_set,
+                            &collider_components_set,
+                        );
+                    }
                 }
                 sim_to_render_time.diff -= sim_dt;
             }
@@ -319,6 +702,16 @@ pub fn step_world_system<UserData: 'static + WorldQuery>(
                         time.delta_seconds().min(integration_parameters.dt);
                 }
 
+                apply_forces_before_step(
+                    new_integration_parameters.dt,
+                    &configuration,
+                    (&mut islands, &narrow_phase, &mut joints),
+                    (&mut rigid_body_components_set, &collider_components_set),
+                    &mut forces,
+                    #[cfg(feature = "dim3")]
+                    (&query_pipeline, &mut vehicle_wheels),
+                );
+
                 pipeline.step_generic(
                     &configuration.gravity,
                     &new_integration_parameters,
@@ -336,6 +729,13 @@ pub fn step_world_system<UserData: 'static + WorldQuery>(
                     &events,
                 );
 
+                apply_velocity_limits(&mut rigid_body_components_set, &velocity_limits);
+                update_joint_reactions(
+                    new_integration_parameters.dt,
+                    &joints,
+                    &rigid_body_components_set,
+                    &mut joint_reactions,
+                );
                 modifs_tracker.clear_modified_and_removed();
             }
         }
@@ -366,6 +766,12 @@ pub(crate) fn sync_transform(pos: &Isometry<f32>, scale: f32, transform: &mut Tr
     transform.rotation = rot;
 }
 
+/// Converts a `GlobalTransform` into a position in physics units, i.e. with its translation
+/// divided by `RapierConfiguration::scale`.
+pub(crate) fn physics_isometry(transform: &GlobalTransform, scale: Real) -> Isometry<Real> {
+    (transform.translation / scale, transform.rotation).into()
+}
+
 /// System responsible for writing the rigid-bodies positions into the Bevy translation and rotation components.
 pub fn sync_transforms(
     mut commands: Commands,
@@ -494,6 +900,243 @@ pub fn collect_removals(
     removed_bodies: RemovedComponents<RigidBodyChanges>,
     removed_colliders: RemovedComponents<ColliderChanges>,
     removed_joints: RemovedComponents<JointHandleComponent>,
+    removed_joint_lists: RemovedComponents<Joints>,
 ) {
-    modification_tracker.detect_removals(removed_bodies, removed_colliders, removed_joints);
+    modification_tracker.detect_removals(
+        removed_bodies,
+        removed_colliders,
+        removed_joints,
+        removed_joint_lists,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::{test_app, ColliderBundle, RigidBodyBundle};
+    use crate::rapier::dynamics::BallJoint;
+    use crate::rapier::math::Point;
+    use bevy::app::{Events, ManualEventReader};
+
+    fn spawn_body(app: &mut App) -> Entity {
+        app.world
+            .spawn()
+            .insert_bundle(RigidBodyBundle::default())
+            .insert_bundle(ColliderBundle::default())
+            .id()
+    }
+
+    fn ball_joint() -> BallJoint {
+        BallJoint::new(Point::origin(), Point::origin())
+    }
+
+    fn spawn_joint(app: &mut App, entity1: Entity, entity2: Entity) -> Entity {
+        app.world
+            .spawn()
+            .insert(JointBuilderComponent::new(ball_joint(), entity1, entity2))
+            .id()
+    }
+
+    /// Runs `updates` updates, and returns the `JointCreationError`s sent during them.
+    fn update_with_errors(app: &mut App, updates: usize) -> Vec<JointCreationError> {
+        let mut reader = ManualEventReader::default();
+        let mut errors = vec![];
+
+        for _ in 0..updates {
+            app.update();
+            let events = app
+                .world
+                .get_resource::<Events<JointCreationError>>()
+                .unwrap();
+            errors.extend(reader.iter(events).copied());
+        }
+
+        errors
+    }
+
+    fn set_joint_creation_timeout(app: &mut App, timeout: Option<u32>) {
+        app.world
+            .get_resource_mut::<RapierConfiguration>()
+            .unwrap()
+            .joint_creation_timeout = timeout;
+    }
+
+    #[test]
+    fn joint_to_despawned_entity_is_abandoned() {
+        let mut app = test_app();
+        let body = spawn_body(&mut app);
+        let despawned = spawn_body(&mut app);
+        app.world.despawn(despawned);
+        let joint = spawn_joint(&mut app, body, despawned);
+
+        let errors = update_with_errors(&mut app, 2);
+
+        assert!(app.world.get::<JointBuilderComponent>(joint).is_none());
+        assert!(app.world.get::<JointHandleComponent>(joint).is_none());
+        assert_eq!(
+            errors,
+            vec![JointCreationError {
+                joint,
+                entity: despawned,
+                kind: JointCreationErrorKind::EntityNotFound,
+            }]
+        );
+    }
+
+    #[test]
+    fn joint_without_rigid_body_times_out() {
+        let mut app = test_app();
+        set_joint_creation_timeout(&mut app, Some(5));
+        let body = spawn_body(&mut app);
+        let not_a_body = app.world.spawn().id();
+        let late_body = app.world.spawn().id();
+        let joint = spawn_joint(&mut app, body, not_a_body);
+        let late_joint = spawn_joint(&mut app, late_body, body);
+
+        let errors = update_with_errors(&mut app, 3);
+        assert!(errors.is_empty());
+        let builder = app.world.get::<JointBuilderComponent>(joint).unwrap();
+        assert_eq!(builder.pending_frames(), 3);
+
+        // A joint whose rigid-body is added before the timeout is still created.
+        app.world
+            .entity_mut(late_body)
+            .insert_bundle(RigidBodyBundle::default());
+        let errors = update_with_errors(&mut app, 5);
+
+        assert!(app.world.get::<JointBuilderComponent>(joint).is_none());
+        assert!(app.world.get::<JointHandleComponent>(late_joint).is_some());
+        assert_eq!(
+            errors,
+            vec![JointCreationError {
+                joint,
+                entity: not_a_body,
+                kind: JointCreationErrorKind::RigidBodyNotFound,
+            }]
+        );
+    }
+
+    #[test]
+    fn joint_without_timeout_stays_pending() {
+        let mut app = test_app();
+        set_joint_creation_timeout(&mut app, None);
+        let body = spawn_body(&mut app);
+        let not_a_body = app.world.spawn().id();
+        let joint = spawn_joint(&mut app, body, not_a_body);
+
+        let errors = update_with_errors(&mut app, 10);
+
+        assert!(errors.is_empty());
+        let builder = app.world.get::<JointBuilderComponent>(joint).unwrap();
+        assert_eq!(builder.pending_frames(), 10);
+    }
+
+    #[test]
+    fn joint_list_errors() {
+        let mut app = test_app();
+        set_joint_creation_timeout(&mut app, Some(2));
+        let body = spawn_body(&mut app);
+        let other_body = spawn_body(&mut app);
+        let not_a_body = app.world.spawn().id();
+        let despawned = app.world.spawn().id();
+        app.world.despawn(despawned);
+        app.world.entity_mut(body).insert(
+            Joints::new()
+                .joint(ball_joint(), body, other_body)
+                .joint(ball_joint(), body, despawned)
+                .joint(ball_joint(), not_a_body, body),
+        );
+
+        let errors = update_with_errors(&mut app, 4);
+
+        let list = app.world.get::<Joints>(body).unwrap();
+        assert_eq!(list.handles().len(), 1);
+        assert!(list.pending().is_empty());
+        assert_eq!(
+            errors,
+            vec![
+                JointCreationError {
+                    joint: body,
+                    entity: despawned,
+                    kind: JointCreationErrorKind::EntityNotFound,
+                },
+                JointCreationError {
+                    joint: body,
+                    entity: not_a_body,
+                    kind: JointCreationErrorKind::RigidBodyNotFound,
+                },
+            ]
+        );
+    }
+
+    fn joint_count(app: &App) -> usize {
+        app.world.get_resource::<JointSet>().unwrap().len()
+    }
+
+    fn run_updates(app: &mut App, updates: usize) {
+        for _ in 0..updates {
+            app.update();
+        }
+    }
+
+    #[test]
+    fn removed_joint_list_removes_its_joints() {
+        let mut app = test_app();
+        let bodies: Vec<_> = (0..3).map(|_| spawn_body(&mut app)).collect();
+        let unrelated = spawn_joint(&mut app, bodies[1], bodies[2]);
+        app.world.entity_mut(bodies[0]).insert(
+            Joints::new()
+                .joint(ball_joint(), bodies[0], bodies[1])
+                .joint(ball_joint(), bodies[0], bodies[2]),
+        );
+        run_updates(&mut app, 2);
+        assert_eq!(joint_count(&app), 3);
+
+        app.world.entity_mut(bodies[0]).remove::<Joints>();
+        run_updates(&mut app, 2);
+
+        let unrelated = app.world.get::<JointHandleComponent>(unrelated).unwrap();
+        let joints = app.world.get_resource::<JointSet>().unwrap();
+        assert_eq!(joints.len(), 1);
+        assert!(joints.get(unrelated.handle()).is_some());
+    }
+
+    #[test]
+    fn despawned_joint_list_removes_its_joints() {
+        let mut app = test_app();
+        let bodies: Vec<_> = (0..2).map(|_| spawn_body(&mut app)).collect();
+        let list = app
+            .world
+            .spawn()
+            .insert(Joints::new().joint(ball_joint(), bodies[0], bodies[1]))
+            .id();
+        run_updates(&mut app, 2);
+        assert_eq!(joint_count(&app), 1);
+
+        app.world.despawn(list);
+        run_updates(&mut app, 2);
+
+        assert_eq!(joint_count(&app), 0);
+    }
+
+    #[test]
+    fn despawned_body_removes_its_listed_joints() {
+        let mut app = test_app();
+        let bodies: Vec<_> = (0..3).map(|_| spawn_body(&mut app)).collect();
+        app.world.entity_mut(bodies[0]).insert(
+            Joints::new()
+                .joint(ball_joint(), bodies[0], bodies[1])
+                .joint(ball_joint(), bodies[0], bodies[2]),
+        );
+        run_updates(&mut app, 2);
+        assert_eq!(joint_count(&app), 2);
+
+        app.world.despawn(bodies[2]);
+        run_updates(&mut app, 2);
+
+        let list = app.world.get::<Joints>(bodies[0]).unwrap();
+        assert_eq!(list.handles().len(), 1);
+        assert_eq!(list.handles()[0].entity2(), bodies[1]);
+        assert_eq!(joint_count(&app), 1);
+    }
 }
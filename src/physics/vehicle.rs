@@ -0,0 +1,271 @@
+use crate::physics::{
+    wake_up_dynamic_body, ColliderComponentsSet, IntoEntity, IntoHandle, RigidBodyComponentsSet,
+};
+use crate::rapier::data::{ComponentSet, ComponentSetMut, ComponentSetOption, Index};
+use crate::rapier::dynamics::{
+    IslandManager, RigidBodyActivation, RigidBodyForces, RigidBodyPosition, RigidBodyVelocity,
+};
+use crate::rapier::geometry::{ColliderHandle, ColliderParent, InteractionGroups, Ray};
+use crate::rapier::math::{Point, Real, Rotation, Vector};
+use crate::rapier::pipeline::QueryPipeline;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+pub type VehicleWheelsQuery<'a, 'b, 'c> =
+    Query<'a, (&'b Parent, &'b VehicleWheel, &'c mut VehicleWheelState)>;
+
+/// A component describing a wheel of a raycast vehicle.
+///
+/// The entity with this component must be a child of the entity of the vehicle's chassis,
+/// which must be a dynamic rigid-body. At each physics step, a ray is cast from the
+/// connection point along the suspension direction, and the suspension, engine, brake,
+/// and friction forces of the wheel are applied to the chassis. The resulting state of the
+/// wheel is written into the `VehicleWheelState` component of the same entity.
+///
+/// All the positions and directions are expressed in the local frame of the chassis, in
+/// physics units.
+#[derive(Copy, Clone, Debug)]
+pub struct VehicleWheel {
+    /// The point where the suspension is attached to the chassis.
+    pub chassis_connection_point: Point<Real>,
+    /// The direction of the suspension, pointing toward the ground.
+    pub suspension_direction: Vector<Real>,
+    /// The rotation axis of the wheel, when it isn't steering.
+    pub axle: Vector<Real>,
+    /// The radius of the wheel.
+    pub radius: Real,
+    /// The length of the suspension when it is neither compressed nor extended.
+    pub suspension_rest_length: Real,
+    /// The maximum distance the suspension can be compressed or extended from its rest length.
+    pub max_suspension_travel: Real,
+    /// The stiffness of the suspension spring.
+    pub suspension_stiffness: Real,
+    /// The damping of the suspension spring.
+    pub suspension_damping: Real,
+    /// The maximum force the suspension can apply.
+    pub max_suspension_force: Real,
+    /// The friction coefficient of the tire. The ground friction force applied by the wheel is
+    /// at most `friction_slip * suspension_force`.
+    pub friction_slip: Real,
+    /// The steering angle of the wheel, in radians, around the suspension direction.
+    pub steering: Real,
+    /// The force applied by the engine along the forward direction of the wheel.
+    pub engine_force: Real,
+    /// The maximum force applied by the brake against the forward motion of the wheel.
+    pub brake: Real,
+    /// The interaction groups used to filter the colliders hit by the wheel's ray.
+    pub groups: InteractionGroups,
+}
+
+impl Default for VehicleWheel {
+    fn default() -> Self {
+        Self {
+            chassis_connection_point: Point::origin(),
+            suspension_direction: -Vector::y(),
+            axle: -Vector::x(),
+            radius: 0.5,
+            suspension_rest_length: 0.3,
+            max_suspension_travel: 0.2,
+            suspension_stiffness: 50.0,
+            suspension_damping: 5.0,
+            max_suspension_force: 6000.0,
+            friction_slip: 1.0,
+            steering: 0.0,
+            engine_force: 0.0,
+            brake: 0.0,
+            groups: InteractionGroups::all(),
+        }
+    }
+}
+
+impl VehicleWheel {
+    /// A wheel with the given radius, attached to the given point of the chassis.
+    pub fn new(chassis_connection_point: Point<Real>, radius: Real) -> Self {
+        Self {
+            chassis_connection_point,
+            radius,
+            ..Default::default()
+        }
+    }
+}
+
+/// The state of a `VehicleWheel` after the last physics step.
+#[derive(Copy, Clone, Debug)]
+pub struct VehicleWheelState {
+    /// Is the wheel touching the ground?
+    pub in_contact: bool,
+    /// The entity of the collider touched by the wheel, if any.
+    pub ground: Option<Entity>,
+    /// The world-space point where the wheel touches the ground.
+    pub contact_point: Point<Real>,
+    /// The world-space normal of the ground at the contact point.
+    pub contact_normal: Vector<Real>,
+    /// The current length of the suspension.
+    pub suspension_length: Real,
+    /// The force currently applied by the suspension.
+    pub suspension_force: Real,
+    /// The speed of the chassis along the forward direction of the wheel.
+    pub forward_speed: Real,
+    /// The accumulated rotation angle of the wheel around its axle, in radians.
+    pub rotation: Real,
+    /// Is the tire slipping because the ground friction force reached its limit?
+    pub skidding: bool,
+}
+
+impl Default for VehicleWheelState {
+    fn default() -> Self {
+        Self {
+            in_contact: false,
+            ground: None,
+            contact_point: Point::origin(),
+            contact_normal: Vector::y(),
+            suspension_length: 0.0,
+            suspension_force: 0.0,
+            forward_speed: 0.0,
+            rotation: 0.0,
+            skidding: false,
+        }
+    }
+}
+
+/// A bundle of the components describing a wheel of a raycast vehicle.
+#[derive(Bundle, Default)]
+pub struct VehicleWheelBundle {
+    pub wheel: VehicleWheel,
+    pub state: VehicleWheelState,
+}
+
+/// Casts the rays of the `VehicleWheel`s and applies their forces to their chassis.
+pub(crate) fn update_vehicle_wheels(
+    dt: Real,
+    query_pipeline: &QueryPipeline,
+    islands: &mut IslandManager,
+    bodies: &mut RigidBodyComponentsSet,
+    colliders: &ColliderComponentsSet,
+    wheels: &mut VehicleWheelsQuery,
+) {
+    if dt <= 0.0 {
+        return;
+    }
+
+    // The mass of each chassis is evenly distributed between its wheels.
+    let mut wheel_counts: HashMap<Entity, usize> = HashMap::new();
+    for (parent, _, _) in wheels.iter_mut() {
+        *wheel_counts.entry(parent.0).or_default() += 1;
+    }
+
+    for (parent, wheel, mut state) in wheels.iter_mut() {
+        let chassis = parent.0;
+        let handle: Index = chassis.handle();
+
+        // Wake-up sleeping chassis when the engine is on, but leave them alone otherwise.
+        let rb_activation: Option<&RigidBodyActivation> = bodies.get(handle);
+        let rb_mprops = match rb_activation {
+            Some(rb_activation) if !rb_activation.sleeping || wheel.engine_force != 0.0 => {
+                match wake_up_dynamic_body(islands, bodies, handle) {
+                    Some(rb_mprops) => rb_mprops,
+                    None => continue,
+                }
+            }
+            _ => continue,
+        };
+
+        let (rb_pos, rb_vels): (&RigidBodyPosition, &RigidBodyVelocity) =
+            (bodies.index(handle), bodies.index(handle));
+        let (chassis_pos, chassis_vels) = (rb_pos.position, *rb_vels);
+        let wheel_count = wheel_counts[&chassis];
+
+        let suspension_dir = chassis_pos * wheel.suspension_direction.normalize();
+        let steering = Rotation::from_scaled_axis(-suspension_dir * wheel.steering);
+        let axle = steering * (chassis_pos * wheel.axle);
+        let origin = chassis_pos * wheel.chassis_connection_point;
+
+        let min_length = (wheel.suspension_rest_length - wheel.max_suspension_travel).max(0.0);
+        let max_length = wheel.suspension_rest_length + wheel.max_suspension_travel;
+        let ray = Ray::new(origin, suspension_dir);
+        let filter = |co_handle: ColliderHandle| {
+            let co_parent: Option<&ColliderParent> = colliders.get(co_handle.0);
+            co_parent.map(|p| p.handle.entity()) != Some(chassis)
+        };
+        let hit = query_pipeline.cast_ray_and_get_normal(
+            colliders,
+            &ray,
+            max_length + wheel.radius,
+            true,
+            wheel.groups,
+            Some(&filter),
+        );
+
+        let (co_handle, intersection) = match hit {
+            Some(hit) => hit,
+            None => {
+                state.in_contact = false;
+                state.ground = None;
+                state.suspension_length = max_length;
+                state.suspension_force = 0.0;
+                state.skidding = false;
+                state.rotation += state.forward_speed / wheel.radius * dt;
+                continue;
+            }
+        };
+
+        let contact_point = ray.point_at(intersection.toi);
+        let normal = intersection.normal;
+        let suspension_length = (intersection.toi - wheel.radius)
+            .max(min_length)
+            .min(max_length);
+        let point_vel = chassis_vels.velocity_at_point(&contact_point, &rb_mprops.world_com);
+
+        // Suspension.
+        let denominator = -normal.dot(&suspension_dir);
+        let projected_vel = if denominator > 0.1 {
+            -point_vel.dot(&normal) / denominator
+        } else {
+            0.0
+        };
+        let suspension_force = (wheel.suspension_stiffness
+            * (wheel.suspension_rest_length - suspension_length)
+            + wheel.suspension_damping * projected_vel)
+            .max(0.0)
+            .min(wheel.max_suspension_force);
+
+        // Engine, brake, and side friction, along the ground plane.
+        let side = (axle - normal * axle.dot(&normal))
+            .try_normalize(1.0e-6)
+            .unwrap_or_else(Vector::zeros);
+        let forward = normal.cross(&side);
+        let forward_speed = point_vel.dot(&forward);
+        let side_speed = point_vel.dot(&side);
+        // The mass supported by this wheel.
+        let mass = rb_mprops.mass() / wheel_count as Real;
+
+        let max_brake = forward_speed.abs() * mass / dt;
+        let brake = -forward_speed.signum() * wheel.brake.min(max_brake);
+        let mut forward_force = wheel.engine_force + brake;
+        let mut side_force = -side_speed * mass / dt;
+
+        let max_friction = wheel.friction_slip * suspension_force;
+        let friction = (forward_force * forward_force + side_force * side_force).sqrt();
+        let skidding = friction > max_friction;
+        if skidding {
+            let ratio = max_friction / friction;
+            forward_force *= ratio;
+            side_force *= ratio;
+        }
+
+        let force = normal * suspension_force + forward * forward_force + side * side_force;
+        bodies.map_mut_internal(handle, |rb_forces: &mut RigidBodyForces| {
+            rb_forces.apply_force_at_point(&rb_mprops, force, contact_point)
+        });
+
+        state.in_contact = true;
+        state.ground = Some(co_handle.entity());
+        state.contact_point = contact_point;
+        state.contact_normal = normal;
+        state.suspension_length = suspension_length;
+        state.suspension_force = suspension_force;
+        state.forward_speed = forward_speed;
+        state.rotation += forward_speed / wheel.radius * dt;
+        state.skidding = skidding;
+    }
+}
@@ -0,0 +1,122 @@
+use crate::physics::{IntoHandle, RigidBodyComponentsSet};
+use crate::rapier::data::{ComponentSetMut, ComponentSetOption, Index};
+use crate::rapier::dynamics::RigidBodyVelocity;
+use crate::rapier::math::Real;
+use bevy::prelude::*;
+
+/// A component limiting the linear and angular speed of the rigid-body of the same entity.
+///
+/// The limits are enforced after each physics step (including each step performed during a
+/// single frame with the `TimestepMode::InterpolatedTimestep`).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct VelocityLimits {
+    /// The maximum norm of the linear velocity of the rigid-body.
+    pub max_linear_speed: Real,
+    /// The maximum norm of the angular velocity of the rigid-body.
+    pub max_angular_speed: Real,
+}
+
+impl Default for VelocityLimits {
+    fn default() -> Self {
+        Self {
+            max_linear_speed: Real::MAX,
+            max_angular_speed: Real::MAX,
+        }
+    }
+}
+
+impl VelocityLimits {
+    /// Limits the linear and angular speeds of a rigid-body.
+    pub fn new(max_linear_speed: Real, max_angular_speed: Real) -> Self {
+        Self {
+            max_linear_speed,
+            max_angular_speed,
+        }
+    }
+
+    /// The given velocities clamped to these limits, or `None` if they don't exceed them.
+    fn clamp(&self, rb_vels: &RigidBodyVelocity) -> Option<RigidBodyVelocity> {
+        let linear_speed = rb_vels.linvel.norm();
+        #[cfg(feature = "dim2")]
+        let angular_speed = rb_vels.angvel.abs();
+        #[cfg(feature = "dim3")]
+        let angular_speed = rb_vels.angvel.norm();
+
+        if linear_speed <= self.max_linear_speed && angular_speed <= self.max_angular_speed {
+            return None;
+        }
+
+        let mut clamped = *rb_vels;
+
+        if linear_speed > self.max_linear_speed {
+            clamped.linvel *= self.max_linear_speed / linear_speed;
+        }
+
+        if angular_speed > self.max_angular_speed {
+            clamped.angvel *= self.max_angular_speed / angular_speed;
+        }
+
+        Some(clamped)
+    }
+}
+
+/// Clamps the velocities of the rigid-bodies exceeding their `VelocityLimits`.
+pub(crate) fn apply_velocity_limits(
+    bodies: &mut RigidBodyComponentsSet,
+    velocity_limits: &Query<(Entity, &VelocityLimits)>,
+) {
+    for (entity, limits) in velocity_limits.iter() {
+        let handle: Index = entity.handle();
+        let rb_vels: &RigidBodyVelocity = match bodies.get(handle) {
+            Some(rb_vels) => rb_vels,
+            None => continue,
+        };
+
+        // Only write the velocities when needed so we don't trigger change detection.
+        if let Some(clamped) = limits.clamp(rb_vels) {
+            bodies.map_mut_internal(handle, |rb_vels: &mut RigidBodyVelocity| {
+                *rb_vels = clamped;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rapier::math::{AngVector, Vector};
+
+    fn clamp(
+        limits: &VelocityLimits,
+        linvel: Vector<Real>,
+        angvel: AngVector<Real>,
+    ) -> Option<(Vector<Real>, AngVector<Real>)> {
+        limits
+            .clamp(&RigidBodyVelocity { linvel, angvel })
+            .map(|rb_vels| (rb_vels.linvel, rb_vels.angvel))
+    }
+
+    #[test]
+    fn clamp_velocities() {
+        let limits = VelocityLimits::new(5.0, 2.0);
+        #[cfg(feature = "dim2")]
+        let (angvel, clamped_angvel) = (-4.0, -2.0);
+        #[cfg(feature = "dim3")]
+        let (angvel, clamped_angvel) = (Vector::z() * -4.0, Vector::z() * -2.0);
+
+        assert_eq!(clamp(&limits, Vector::x() * 3.0, angvel / 4.0), None);
+        assert_eq!(
+            clamp(&limits, Vector::x() * -10.0, angvel / 4.0),
+            Some((Vector::x() * -5.0, angvel / 4.0))
+        );
+        assert_eq!(
+            clamp(&limits, Vector::x() * 3.0, angvel),
+            Some((Vector::x() * 3.0, clamped_angvel))
+        );
+
+        let (linvel, clamped) = clamp(&limits, Vector::repeat(10.0), angvel).unwrap();
+        assert!((linvel.norm() - 5.0).abs() < 1.0e-5);
+        assert!((linvel.normalize() - Vector::repeat(10.0).normalize()).norm() < 1.0e-6);
+        assert_eq!(clamped, clamped_angvel);
+    }
+}